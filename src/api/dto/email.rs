@@ -0,0 +1,50 @@
+use crate::models::{EmailOutcome, EmailSubscription};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct SaveEmailSubscriptionRequest {
+    pub email: String,
+    pub plugin_id: Option<String>,
+    #[serde(default)]
+    pub outcome: EmailOutcome,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailSubscriptionResponse {
+    pub id: String,
+    pub email: String,
+    pub plugin_id: Option<String>,
+    pub outcome: EmailOutcome,
+    pub enabled: bool,
+    pub last_sent_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<EmailSubscription> for EmailSubscriptionResponse {
+    fn from(subscription: EmailSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            email: subscription.email,
+            plugin_id: subscription.plugin_id,
+            outcome: subscription.outcome,
+            enabled: subscription.enabled,
+            last_sent_at: subscription.last_sent_at,
+            last_error: subscription.last_error,
+            created_at: subscription.created_at,
+            updated_at: subscription.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailSubscriptionsListResponse {
+    pub data: Vec<EmailSubscriptionResponse>,
+}
@@ -1,4 +1,4 @@
-use crate::models::Execution;
+use crate::models::{Artifact, Execution, ExecutionProgress, LogStream};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -14,6 +14,37 @@ pub struct ApplyExecutionRequest {
     pub params: Option<HashMap<String, Value>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ProvideInputRequest {
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteExecutionsQuery {
+    pub plugin_id: Option<String>,
+    // 毫秒时间戳
+    pub before: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteExecutionsResponse {
+    pub deleted: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaitExecutionQuery {
+    // 逗号分隔，例如 Completed,Failed
+    pub status: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadLogsQuery {
+    pub stream: Option<LogStream>,
+    pub offset: Option<u64>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ExecutionResponse {
     pub id: String,
@@ -27,11 +58,28 @@ pub struct ExecutionResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preview_payload: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ExecutionProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_request: Option<String>,
+    pub params: Option<String>,
+    pub plugin_version: Option<String>,
+    pub entry_point: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerun_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggered_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub confirm_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<i64>,
     pub started_at: i64,
     pub finished_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
 }
 
 impl From<Execution> for ExecutionResponse {
@@ -46,10 +94,20 @@ impl From<Execution> for ExecutionResponse {
             stdout: execution.stdout,
             stderr: execution.stderr,
             preview_payload: execution.preview_payload,
+            result: execution.result,
+            error_message: execution.error_message,
+            progress: Some(execution.progress).filter(|progress| !progress.is_empty()),
+            input_request: execution.input_request,
+            params: execution.params,
+            plugin_version: execution.plugin_version,
+            entry_point: execution.entry_point,
+            rerun_of: execution.rerun_of,
+            triggered_by: execution.triggered_by,
             confirm_token: execution.confirm_token,
             expires_at: execution.expires_at,
             started_at: execution.started_at,
             finished_at: execution.finished_at,
+            queue_position: execution.queue_position,
         }
     }
 }
@@ -58,3 +116,27 @@ impl From<Execution> for ExecutionResponse {
 pub struct ExecutionsListResponse {
     pub data: Vec<ExecutionResponse>,
 }
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactResponse {
+    pub name: String,
+    pub size: u64,
+    pub content_type: String,
+    pub modified_at: Option<i64>,
+}
+
+impl From<Artifact> for ArtifactResponse {
+    fn from(artifact: Artifact) -> Self {
+        Self {
+            name: artifact.name,
+            size: artifact.size,
+            content_type: artifact.content_type,
+            modified_at: artifact.modified_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactsListResponse {
+    pub data: Vec<ArtifactResponse>,
+}
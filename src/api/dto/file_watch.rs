@@ -0,0 +1,94 @@
+use crate::error::AppError;
+use crate::models::{FileWatch, WatchEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct SaveFileWatchRequest {
+    pub name: String,
+    pub plugin_id: String,
+    pub path: String,
+    #[serde(default)]
+    pub recursive: bool,
+    // 包含 / 时匹配相对路径，否则匹配文件名；为空时匹配所有文件
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    #[serde(default = "default_events")]
+    pub events: Vec<WatchEvent>,
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: i64,
+    pub path_param: String,
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_events() -> Vec<WatchEvent> {
+    vec![
+        WatchEvent::Created,
+        WatchEvent::Modified,
+        WatchEvent::Deleted,
+    ]
+}
+
+fn default_debounce_ms() -> i64 {
+    1000
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileWatchResponse {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub path: String,
+    pub recursive: bool,
+    pub patterns: Vec<String>,
+    pub events: Vec<WatchEvent>,
+    pub debounce_ms: i64,
+    pub path_param: String,
+    pub params: HashMap<String, Value>,
+    pub enabled: bool,
+    pub last_triggered_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<FileWatch> for FileWatchResponse {
+    type Error = AppError;
+
+    fn try_from(watch: FileWatch) -> Result<Self, Self::Error> {
+        let invalid = |e: serde_json::Error| AppError::Watch(format!("Invalid file watch: {}", e));
+        Ok(Self {
+            patterns: serde_json::from_str(&watch.patterns).map_err(invalid)?,
+            events: serde_json::from_str(&watch.events).map_err(invalid)?,
+            params: serde_json::from_str(&watch.params).map_err(invalid)?,
+            id: watch.id,
+            name: watch.name,
+            plugin_id: watch.plugin_id,
+            path: watch.path,
+            recursive: watch.recursive,
+            debounce_ms: watch.debounce_ms,
+            path_param: watch.path_param,
+            enabled: watch.enabled,
+            last_triggered_at: watch.last_triggered_at,
+            last_execution_id: watch.last_execution_id,
+            last_error: watch.last_error,
+            created_at: watch.created_at,
+            updated_at: watch.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileWatchesListResponse {
+    pub data: Vec<FileWatchResponse>,
+}
@@ -1,3 +1,10 @@
+pub mod email;
 pub mod execution;
+pub mod file_watch;
+pub mod notification;
 pub mod plugin;
+pub mod runtime;
+pub mod schedule;
 pub mod update;
+pub mod webhook;
+pub mod workflow;
@@ -0,0 +1,114 @@
+use crate::error::AppError;
+use crate::models::{
+    DEFAULT_EVENTS, DeliveryStatus, ExecutionStatus, NotificationDelivery, NotificationTarget,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+pub struct SaveNotificationTargetRequest {
+    pub name: String,
+    pub url: String,
+    // 省略时保留原值，空字符串表示删除
+    pub secret: Option<String>,
+    pub plugin_id: Option<String>,
+    #[serde(default = "default_events")]
+    pub events: Vec<ExecutionStatus>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_events() -> Vec<ExecutionStatus> {
+    DEFAULT_EVENTS.to_vec()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationTargetResponse {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub has_secret: bool,
+    pub plugin_id: Option<String>,
+    pub events: Vec<ExecutionStatus>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<NotificationTarget> for NotificationTargetResponse {
+    type Error = AppError;
+
+    fn try_from(target: NotificationTarget) -> Result<Self, Self::Error> {
+        let events = serde_json::from_str(&target.events)
+            .map_err(|e| AppError::Notification(format!("Invalid target events: {}", e)))?;
+        Ok(Self {
+            id: target.id,
+            name: target.name,
+            url: target.url,
+            has_secret: target.secret.is_some(),
+            plugin_id: target.plugin_id,
+            events,
+            enabled: target.enabled,
+            created_at: target.created_at,
+            updated_at: target.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationTargetsListResponse {
+    pub data: Vec<NotificationTargetResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    pub target_id: Option<String>,
+    pub execution_id: Option<String>,
+    pub status: Option<DeliveryStatus>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryResponse {
+    pub id: String,
+    pub target_id: String,
+    pub execution_id: String,
+    pub event: ExecutionStatus,
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<i64>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+impl From<NotificationDelivery> for DeliveryResponse {
+    fn from(delivery: NotificationDelivery) -> Self {
+        Self {
+            payload: serde_json::from_str(&delivery.payload)
+                .unwrap_or(Value::String(delivery.payload)),
+            id: delivery.id,
+            target_id: delivery.target_id,
+            execution_id: delivery.execution_id,
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            last_error: delivery.last_error,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveriesListResponse {
+    pub data: Vec<DeliveryResponse>,
+}
@@ -1,7 +1,10 @@
 use crate::error::AppError;
-use crate::models::{Plugin, PluginParameter, PluginParameterGroup, PythonDependencies};
-use serde_json::Value;
+use crate::models::{
+    DenoPermissions, Plugin, PluginDependencies, PluginParameter, PluginParameterGroup,
+    PythonDependencies,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Deserialize)]
 pub struct InstallPluginRequest {
@@ -20,6 +23,11 @@ pub struct PluginResponse {
     pub version: String,
     pub min_anthill_version: Option<String>,
     pub plugin_type: String,
+    pub runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<DenoPermissions>,
     pub description: String,
     pub author: String,
     pub entry_point: String,
@@ -30,6 +38,10 @@ pub struct PluginResponse {
     pub groups: Option<Vec<PluginParameterGroup>>,
     pub metadata: Option<Value>,
     pub python_dependencies: Option<PythonDependencies>,
+    pub dependencies: Option<PluginDependencies>,
+    pub prepare_timeout_seconds: Option<i64>,
+    pub apply_timeout_seconds: Option<i64>,
+    pub max_concurrency: Option<i64>,
 }
 
 impl TryFrom<Plugin> for PluginResponse {
@@ -40,12 +52,18 @@ impl TryFrom<Plugin> for PluginResponse {
         let groups = parse_groups(&plugin.parameter_groups)?;
         let metadata = parse_metadata(&plugin.metadata)?;
         let python_dependencies = parse_python_dependencies(&plugin.python_dependencies)?;
+        let dependencies = parse_dependencies(&plugin.dependencies)?;
+        let permissions = parse_permissions(&plugin.permissions)?;
+        let runtime = plugin.runtime_name().map(str::to_string);
         Ok(Self {
             id: plugin.plugin_id,
             name: plugin.name,
             version: plugin.version,
             min_anthill_version: plugin.min_anthill_version,
             plugin_type: format!("{:?}", plugin.plugin_type),
+            runtime,
+            interpreter: plugin.interpreter,
+            permissions,
             description: plugin.description,
             author: plugin.author,
             entry_point: plugin.entry_point,
@@ -56,6 +74,10 @@ impl TryFrom<Plugin> for PluginResponse {
             groups,
             metadata,
             python_dependencies,
+            dependencies,
+            prepare_timeout_seconds: plugin.prepare_timeout_seconds,
+            apply_timeout_seconds: plugin.apply_timeout_seconds,
+            max_concurrency: plugin.max_concurrency,
         })
     }
 }
@@ -86,6 +108,32 @@ fn parse_python_dependencies(raw: &Option<String>) -> Result<Option<PythonDepend
     Ok(Some(dependencies))
 }
 
+fn parse_dependencies(raw: &Option<String>) -> Result<Option<PluginDependencies>, AppError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let dependencies = serde_json::from_str(trimmed)
+        .map_err(|e| AppError::Execution(format!("Invalid plugin dependencies: {}", e)))?;
+    Ok(Some(dependencies))
+}
+
+fn parse_permissions(raw: &Option<String>) -> Result<Option<DenoPermissions>, AppError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let permissions = serde_json::from_str(trimmed)
+        .map_err(|e| AppError::Execution(format!("Invalid plugin permissions: {}", e)))?;
+    Ok(Some(permissions))
+}
+
 fn parse_groups(raw: &Option<String>) -> Result<Option<Vec<PluginParameterGroup>>, AppError> {
     let Some(raw) = raw else {
         return Ok(None);
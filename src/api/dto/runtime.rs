@@ -0,0 +1,26 @@
+use crate::models::RuntimeInfo;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct RuntimeResponse {
+    pub name: String,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub available: bool,
+}
+
+impl From<RuntimeInfo> for RuntimeResponse {
+    fn from(info: RuntimeInfo) -> Self {
+        Self {
+            name: info.name,
+            path: info.path,
+            version: info.version,
+            available: info.available,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuntimesListResponse {
+    pub data: Vec<RuntimeResponse>,
+}
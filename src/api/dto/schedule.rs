@@ -0,0 +1,80 @@
+use crate::error::AppError;
+use crate::models::{OverlapPolicy, Schedule};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct SaveScheduleRequest {
+    pub name: String,
+    pub plugin_id: String,
+    pub cron: Option<String>,
+    pub interval_seconds: Option<i64>,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub overlap_policy: OverlapPolicy,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleResponse {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub cron: Option<String>,
+    pub interval_seconds: Option<i64>,
+    pub timezone: String,
+    pub params: HashMap<String, Value>,
+    pub enabled: bool,
+    pub overlap_policy: OverlapPolicy,
+    pub last_run_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub next_run_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<Schedule> for ScheduleResponse {
+    type Error = AppError;
+
+    fn try_from(schedule: Schedule) -> Result<Self, Self::Error> {
+        let params = serde_json::from_str(&schedule.params)
+            .map_err(|e| AppError::Schedule(format!("Invalid schedule params: {}", e)))?;
+        Ok(Self {
+            id: schedule.id,
+            name: schedule.name,
+            plugin_id: schedule.plugin_id,
+            cron: schedule.cron,
+            interval_seconds: schedule.interval_seconds,
+            timezone: schedule.timezone,
+            params,
+            enabled: schedule.enabled,
+            overlap_policy: schedule.overlap_policy,
+            last_run_at: schedule.last_run_at,
+            last_execution_id: schedule.last_execution_id,
+            last_error: schedule.last_error,
+            next_run_at: schedule.next_run_at,
+            created_at: schedule.created_at,
+            updated_at: schedule.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulesListResponse {
+    pub data: Vec<ScheduleResponse>,
+}
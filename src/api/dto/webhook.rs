@@ -0,0 +1,71 @@
+use crate::error::AppError;
+use crate::models::Webhook;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct SaveWebhookRequest {
+    pub name: String,
+    pub plugin_id: String,
+    // 参数名 => 请求体中的 JSON 路径，例如 $.ref
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    #[serde(default)]
+    pub wait_for_result: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TriggerWebhookQuery {
+    pub wait: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub token: String,
+    pub hook_path: String,
+    pub mapping: HashMap<String, String>,
+    pub wait_for_result: bool,
+    pub enabled: bool,
+    pub last_triggered_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<Webhook> for WebhookResponse {
+    type Error = AppError;
+
+    fn try_from(webhook: Webhook) -> Result<Self, Self::Error> {
+        let mapping = serde_json::from_str(&webhook.mapping)
+            .map_err(|e| AppError::Webhook(format!("Invalid webhook mapping: {}", e)))?;
+        Ok(Self {
+            id: webhook.id,
+            name: webhook.name,
+            plugin_id: webhook.plugin_id,
+            hook_path: format!("/api/hooks/{}", webhook.token),
+            token: webhook.token,
+            mapping,
+            wait_for_result: webhook.wait_for_result,
+            enabled: webhook.enabled,
+            last_triggered_at: webhook.last_triggered_at,
+            last_execution_id: webhook.last_execution_id,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhooksListResponse {
+    pub data: Vec<WebhookResponse>,
+}
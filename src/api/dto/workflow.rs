@@ -0,0 +1,79 @@
+use crate::error::AppError;
+use crate::models::{
+    FailurePolicy, Workflow, WorkflowRun, WorkflowRunStatus, WorkflowStep, WorkflowStepRun,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct SaveWorkflowRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    pub failure_policy: FailurePolicy,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    pub failure_policy: FailurePolicy,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<Workflow> for WorkflowResponse {
+    type Error = AppError;
+
+    fn try_from(workflow: Workflow) -> Result<Self, Self::Error> {
+        let steps = serde_json::from_str(&workflow.steps)
+            .map_err(|e| AppError::Workflow(format!("Invalid workflow steps: {}", e)))?;
+        Ok(Self {
+            id: workflow.id,
+            name: workflow.name,
+            description: workflow.description,
+            steps,
+            failure_policy: workflow.failure_policy,
+            created_at: workflow.created_at,
+            updated_at: workflow.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowsListResponse {
+    pub data: Vec<WorkflowResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowRunResponse {
+    pub id: String,
+    pub workflow_id: String,
+    pub status: WorkflowRunStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub steps: Vec<WorkflowStepRun>,
+}
+
+impl From<(WorkflowRun, Vec<WorkflowStepRun>)> for WorkflowRunResponse {
+    fn from((run, steps): (WorkflowRun, Vec<WorkflowStepRun>)) -> Self {
+        Self {
+            id: run.id,
+            workflow_id: run.workflow_id,
+            status: run.status,
+            error_message: run.error_message,
+            started_at: run.started_at,
+            finished_at: run.finished_at,
+            steps,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkflowRunsListResponse {
+    pub data: Vec<WorkflowRunResponse>,
+}
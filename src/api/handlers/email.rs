@@ -0,0 +1,76 @@
+use crate::api::dto::email::{
+    EmailSubscriptionResponse, EmailSubscriptionsListResponse, SaveEmailSubscriptionRequest,
+};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use crate::services::mailer::EmailSubscriptionSettings;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+fn settings(req: SaveEmailSubscriptionRequest) -> EmailSubscriptionSettings {
+    EmailSubscriptionSettings {
+        email: req.email,
+        plugin_id: req.plugin_id,
+        outcome: req.outcome,
+        enabled: req.enabled,
+    }
+}
+
+pub async fn list_subscriptions(
+    State(state): State<AppState>,
+) -> Result<Json<EmailSubscriptionsListResponse>> {
+    let subscriptions = state.mailer.list_subscriptions().await?;
+    Ok(Json(EmailSubscriptionsListResponse {
+        data: subscriptions
+            .into_iter()
+            .map(EmailSubscriptionResponse::from)
+            .collect(),
+    }))
+}
+
+pub async fn get_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<EmailSubscriptionResponse>> {
+    let subscription = state.mailer.get_subscription(&id).await?;
+    Ok(Json(EmailSubscriptionResponse::from(subscription)))
+}
+
+pub async fn create_subscription(
+    State(state): State<AppState>,
+    Json(req): Json<SaveEmailSubscriptionRequest>,
+) -> Result<(StatusCode, Json<EmailSubscriptionResponse>)> {
+    let subscription = state.mailer.create_subscription(settings(req)).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(EmailSubscriptionResponse::from(subscription)),
+    ))
+}
+
+pub async fn update_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveEmailSubscriptionRequest>,
+) -> Result<Json<EmailSubscriptionResponse>> {
+    let subscription = state.mailer.update_subscription(&id, settings(req)).await?;
+    Ok(Json(EmailSubscriptionResponse::from(subscription)))
+}
+
+pub async fn delete_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.mailer.delete_subscription(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn send_test(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.mailer.send_test(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
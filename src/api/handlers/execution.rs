@@ -1,13 +1,41 @@
 use crate::api::dto::execution::{
-    ApplyExecutionRequest, ExecutePluginRequest, ExecutionResponse, ExecutionsListResponse,
+    ApplyExecutionRequest, ArtifactResponse, ArtifactsListResponse, DeleteExecutionsQuery,
+    DeleteExecutionsResponse, ExecutePluginRequest, ExecutionResponse, ExecutionsListResponse,
+    ProvideInputRequest, ReadLogsQuery, WaitExecutionQuery,
 };
 use crate::api::routes::AppState;
-use crate::error::Result;
-use crate::models::ExecutionStatus;
+use crate::error::{AppError, Result};
+use crate::models::{Execution, ExecutionPhase, ExecutionStatus, LogStream};
+use crate::services::log_store::LogChunk;
+use crate::services::log_stream::LogEvent;
 use axum::{
     Json,
-    extract::{Path, Query, State},
+    body::Body,
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade, rejection::WebSocketUpgradeRejection},
+    },
+    http::{StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use tokio::sync::mpsc;
+
+const DEFAULT_WAIT_MS: u64 = 30_000;
+pub(super) const MAX_WAIT_MS: u64 = 60_000;
+
+// 没有进程在处理该执行的状态
+pub(super) const SETTLED_STATUSES: [ExecutionStatus; 7] = [
+    ExecutionStatus::PreviewReady,
+    ExecutionStatus::AwaitingInput,
+    ExecutionStatus::Completed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Stopped,
+    ExecutionStatus::TimedOut,
+    ExecutionStatus::Expired,
+];
 
 pub async fn execute_plugin(
     State(state): State<AppState>,
@@ -33,18 +61,39 @@ pub async fn prepare_plugin(
         .execution_service
         .prepare_plugin(&plugin_id, params)
         .await?;
-    // 等待预览完成或失败，最多 15s
-    let execution = state
-        .execution_service
-        .wait_for_states(
-            &execution.id,
-            &[ExecutionStatus::PreviewReady, ExecutionStatus::Failed],
-            15_000,
-        )
-        .await?;
+    let execution = wait_for_preview(&state, &execution.id).await?;
+    Ok(Json(ExecutionResponse::from(execution)))
+}
+
+pub async fn discard_execution(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ExecutionResponse>> {
+    let execution = state.execution_service.discard_preview(&id).await?;
+    Ok(Json(ExecutionResponse::from(execution)))
+}
+
+pub async fn rerun_execution(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ExecutePluginRequest>,
+) -> Result<Json<ExecutionResponse>> {
+    let params = req.params.unwrap_or_default();
+    let mut execution = state.execution_service.rerun_execution(&id, params).await?;
+    if execution.phase == ExecutionPhase::Prepare {
+        execution = wait_for_preview(&state, &execution.id).await?;
+    }
     Ok(Json(ExecutionResponse::from(execution)))
 }
 
+async fn wait_for_preview(state: &AppState, id: &str) -> Result<Execution> {
+    // 等待预览完成或执行结束，最多 15s
+    state
+        .execution_service
+        .wait_for_states(id, &SETTLED_STATUSES, 15_000)
+        .await
+}
+
 pub async fn apply_execution(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -82,6 +131,38 @@ pub async fn list_executions(
     Ok(Json(response))
 }
 
+pub async fn provide_input(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ProvideInputRequest>,
+) -> Result<Json<ExecutionResponse>> {
+    state
+        .execution_service
+        .provide_input(&id, req.value)
+        .await?;
+    let execution = state.execution_service.get_execution(&id).await?;
+    Ok(Json(ExecutionResponse::from(execution)))
+}
+
+pub async fn delete_execution(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.execution_service.delete_execution(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn delete_executions(
+    State(state): State<AppState>,
+    Query(query): Query<DeleteExecutionsQuery>,
+) -> Result<Json<DeleteExecutionsResponse>> {
+    let deleted = state
+        .execution_service
+        .delete_executions(query.plugin_id.as_deref(), query.before)
+        .await?;
+    Ok(Json(DeleteExecutionsResponse { deleted }))
+}
+
 pub async fn stop_execution(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -91,3 +172,136 @@ pub async fn stop_execution(
         "message": "Execution stopped"
     })))
 }
+
+pub async fn stream_execution_logs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ws: std::result::Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Result<Response> {
+    let events = state.execution_service.follow_logs(&id).await?;
+
+    // 优先使用 SSE，请求为 WebSocket 升级时回退到 WebSocket
+    if let Ok(ws) = ws {
+        return Ok(ws.on_upgrade(move |socket| send_log_events(socket, events)));
+    }
+
+    let stream = futures_util::stream::unfold(events, |mut events| async move {
+        let event = events.recv().await?;
+        let sse_event = Event::default().event(event.name()).json_data(&event);
+        Some((sse_event, events))
+    });
+    Ok(Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+pub async fn wait_execution(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<WaitExecutionQuery>,
+) -> Result<Json<ExecutionResponse>> {
+    let targets = match query.status.as_deref() {
+        Some(list) if !list.trim().is_empty() => parse_statuses(list)?,
+        _ => SETTLED_STATUSES.to_vec(),
+    };
+    let timeout_ms = query.timeout_ms.unwrap_or(DEFAULT_WAIT_MS).min(MAX_WAIT_MS);
+    let execution = state
+        .execution_service
+        .wait_for_states(&id, &targets, timeout_ms)
+        .await?;
+    Ok(Json(ExecutionResponse::from(execution)))
+}
+
+fn parse_statuses(list: &str) -> Result<Vec<ExecutionStatus>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .map_err(|_| AppError::Execution(format!("Unknown execution status: {}", name)))
+        })
+        .collect()
+}
+
+pub async fn read_execution_logs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ReadLogsQuery>,
+) -> Result<Json<LogChunk>> {
+    let chunk = state
+        .execution_service
+        .read_logs(
+            &id,
+            query.stream.unwrap_or(LogStream::Stdout),
+            query.offset.unwrap_or(0),
+            query.limit,
+        )
+        .await?;
+    Ok(Json(chunk))
+}
+
+pub async fn list_artifacts(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ArtifactsListResponse>> {
+    let artifacts = state.execution_service.list_artifacts(&id).await?;
+    Ok(Json(ArtifactsListResponse {
+        data: artifacts.into_iter().map(ArtifactResponse::from).collect(),
+    }))
+}
+
+pub async fn download_artifact(
+    State(state): State<AppState>,
+    Path((id, name)): Path<(String, String)>,
+) -> Result<Response> {
+    let (artifact, path) = state.execution_service.find_artifact(&id, &name).await?;
+    let file = tokio::fs::File::open(&path).await?;
+    let file_name = artifact.name.rsplit('/').next().unwrap_or(&artifact.name);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, artifact.content_type.clone()),
+            (header::CONTENT_LENGTH, artifact.size.to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition(file_name)),
+        ],
+        Body::from_stream(tokio_util::io::ReaderStream::new(file)),
+    )
+        .into_response())
+}
+
+// RFC 6266：filename 只放 ASCII 回退名，完整文件名以 UTF-8 百分号编码放在 filename*
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| match c {
+            ' ' => c,
+            '"' | '\\' | '%' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .collect();
+    let mut encoded = String::with_capacity(file_name.len());
+    for byte in file_name.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+async fn send_log_events(mut socket: WebSocket, mut events: mpsc::Receiver<LogEvent>) {
+    while let Some(event) = events.recv().await {
+        let Ok(text) = serde_json::to_string(&event) else {
+            continue;
+        };
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            return;
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
@@ -0,0 +1,71 @@
+use crate::api::dto::file_watch::{
+    FileWatchResponse, FileWatchesListResponse, SaveFileWatchRequest,
+};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use crate::services::file_watcher::FileWatchSettings;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+fn settings(req: SaveFileWatchRequest) -> FileWatchSettings {
+    FileWatchSettings {
+        name: req.name,
+        plugin_id: req.plugin_id,
+        path: req.path,
+        recursive: req.recursive,
+        patterns: req.patterns,
+        events: req.events,
+        debounce_ms: req.debounce_ms,
+        path_param: req.path_param,
+        params: req.params,
+        enabled: req.enabled,
+    }
+}
+
+pub async fn list_watches(State(state): State<AppState>) -> Result<Json<FileWatchesListResponse>> {
+    let watches = state.file_watcher.list_watches().await?;
+    let data = watches
+        .into_iter()
+        .map(FileWatchResponse::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(FileWatchesListResponse { data }))
+}
+
+pub async fn get_watch(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<FileWatchResponse>> {
+    let watch = state.file_watcher.get_watch(&id).await?;
+    Ok(Json(FileWatchResponse::try_from(watch)?))
+}
+
+pub async fn create_watch(
+    State(state): State<AppState>,
+    Json(req): Json<SaveFileWatchRequest>,
+) -> Result<(StatusCode, Json<FileWatchResponse>)> {
+    let watch = state.file_watcher.create_watch(settings(req)).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(FileWatchResponse::try_from(watch)?),
+    ))
+}
+
+pub async fn update_watch(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveFileWatchRequest>,
+) -> Result<Json<FileWatchResponse>> {
+    let watch = state.file_watcher.update_watch(&id, settings(req)).await?;
+    Ok(Json(FileWatchResponse::try_from(watch)?))
+}
+
+pub async fn delete_watch(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.file_watcher.delete_watch(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
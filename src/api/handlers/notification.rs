@@ -0,0 +1,109 @@
+use crate::api::dto::notification::{
+    DeliveriesListResponse, DeliveryResponse, ListDeliveriesQuery, NotificationTargetResponse,
+    NotificationTargetsListResponse, SaveNotificationTargetRequest,
+};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use crate::repository::notification_repository::DeliveryFilter;
+use crate::services::notifier::NotificationTargetSettings;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 100;
+const MAX_DELIVERY_LIMIT: i64 = 1000;
+
+fn settings(req: SaveNotificationTargetRequest) -> NotificationTargetSettings {
+    NotificationTargetSettings {
+        name: req.name,
+        url: req.url,
+        secret: req.secret,
+        plugin_id: req.plugin_id,
+        events: req.events,
+        enabled: req.enabled,
+    }
+}
+
+pub async fn list_targets(
+    State(state): State<AppState>,
+) -> Result<Json<NotificationTargetsListResponse>> {
+    let targets = state.notifier.list_targets().await?;
+    let data = targets
+        .into_iter()
+        .map(NotificationTargetResponse::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(NotificationTargetsListResponse { data }))
+}
+
+pub async fn get_target(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<NotificationTargetResponse>> {
+    let target = state.notifier.get_target(&id).await?;
+    Ok(Json(NotificationTargetResponse::try_from(target)?))
+}
+
+pub async fn create_target(
+    State(state): State<AppState>,
+    Json(req): Json<SaveNotificationTargetRequest>,
+) -> Result<(StatusCode, Json<NotificationTargetResponse>)> {
+    let target = state.notifier.create_target(settings(req)).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(NotificationTargetResponse::try_from(target)?),
+    ))
+}
+
+pub async fn update_target(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveNotificationTargetRequest>,
+) -> Result<Json<NotificationTargetResponse>> {
+    let target = state.notifier.update_target(&id, settings(req)).await?;
+    Ok(Json(NotificationTargetResponse::try_from(target)?))
+}
+
+pub async fn delete_target(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.notifier.delete_target(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<DeliveriesListResponse>> {
+    let filter = DeliveryFilter {
+        target_id: query.target_id,
+        execution_id: query.execution_id,
+        status: query.status,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+            .clamp(1, MAX_DELIVERY_LIMIT),
+    };
+    let deliveries = state.notifier.list_deliveries(&filter).await?;
+    Ok(Json(DeliveriesListResponse {
+        data: deliveries.into_iter().map(DeliveryResponse::from).collect(),
+    }))
+}
+
+pub async fn get_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DeliveryResponse>> {
+    let delivery = state.notifier.get_delivery(&id).await?;
+    Ok(Json(DeliveryResponse::from(delivery)))
+}
+
+pub async fn retry_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DeliveryResponse>> {
+    let delivery = state.notifier.retry_delivery(&id).await?;
+    Ok(Json(DeliveryResponse::from(delivery)))
+}
@@ -0,0 +1,10 @@
+use crate::api::dto::runtime::{RuntimeResponse, RuntimesListResponse};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use axum::{Json, extract::State};
+
+pub async fn list_runtimes(State(state): State<AppState>) -> Result<Json<RuntimesListResponse>> {
+    let runtimes = state.runtime_service.list_runtimes().await;
+    let data = runtimes.into_iter().map(RuntimeResponse::from).collect();
+    Ok(Json(RuntimesListResponse { data }))
+}
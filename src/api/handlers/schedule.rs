@@ -0,0 +1,67 @@
+use crate::api::dto::schedule::{SaveScheduleRequest, ScheduleResponse, SchedulesListResponse};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use crate::services::scheduler::ScheduleSettings;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+fn settings(req: SaveScheduleRequest) -> ScheduleSettings {
+    ScheduleSettings {
+        name: req.name,
+        plugin_id: req.plugin_id,
+        cron: req.cron,
+        interval_seconds: req.interval_seconds,
+        timezone: req.timezone,
+        params: req.params,
+        enabled: req.enabled,
+        overlap_policy: req.overlap_policy,
+    }
+}
+
+pub async fn list_schedules(State(state): State<AppState>) -> Result<Json<SchedulesListResponse>> {
+    let schedules = state.scheduler.list_schedules().await?;
+    let data = schedules
+        .into_iter()
+        .map(ScheduleResponse::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(SchedulesListResponse { data }))
+}
+
+pub async fn get_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScheduleResponse>> {
+    let schedule = state.scheduler.get_schedule(&id).await?;
+    Ok(Json(ScheduleResponse::try_from(schedule)?))
+}
+
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    Json(req): Json<SaveScheduleRequest>,
+) -> Result<(StatusCode, Json<ScheduleResponse>)> {
+    let schedule = state.scheduler.create_schedule(settings(req)).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(ScheduleResponse::try_from(schedule)?),
+    ))
+}
+
+pub async fn update_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveScheduleRequest>,
+) -> Result<Json<ScheduleResponse>> {
+    let schedule = state.scheduler.update_schedule(&id, settings(req)).await?;
+    Ok(Json(ScheduleResponse::try_from(schedule)?))
+}
+
+pub async fn delete_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.scheduler.delete_schedule(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
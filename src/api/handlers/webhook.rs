@@ -0,0 +1,117 @@
+use super::execution::{MAX_WAIT_MS, SETTLED_STATUSES};
+use crate::api::dto::execution::ExecutionResponse;
+use crate::api::dto::webhook::{
+    SaveWebhookRequest, TriggerWebhookQuery, WebhookResponse, WebhooksListResponse,
+};
+use crate::api::routes::AppState;
+use crate::error::{AppError, Result};
+use crate::services::webhook_service::WebhookSettings;
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde_json::Value;
+
+fn settings(req: SaveWebhookRequest) -> WebhookSettings {
+    WebhookSettings {
+        name: req.name,
+        plugin_id: req.plugin_id,
+        mapping: req.mapping,
+        wait_for_result: req.wait_for_result,
+        enabled: req.enabled,
+    }
+}
+
+pub async fn list_webhooks(State(state): State<AppState>) -> Result<Json<WebhooksListResponse>> {
+    let webhooks = state.webhook_service.list_webhooks().await?;
+    let data = webhooks
+        .into_iter()
+        .map(WebhookResponse::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(WebhooksListResponse { data }))
+}
+
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookResponse>> {
+    let webhook = state.webhook_service.get_webhook(&id).await?;
+    Ok(Json(WebhookResponse::try_from(webhook)?))
+}
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Json(req): Json<SaveWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookResponse>)> {
+    let webhook = state.webhook_service.create_webhook(settings(req)).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(WebhookResponse::try_from(webhook)?),
+    ))
+}
+
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveWebhookRequest>,
+) -> Result<Json<WebhookResponse>> {
+    let webhook = state
+        .webhook_service
+        .update_webhook(&id, settings(req))
+        .await?;
+    Ok(Json(WebhookResponse::try_from(webhook)?))
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.webhook_service.delete_webhook(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn rotate_webhook_token(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookResponse>> {
+    let webhook = state.webhook_service.rotate_token(&id).await?;
+    Ok(Json(WebhookResponse::try_from(webhook)?))
+}
+
+// 返回 202 和已启动的执行；等待结果且按时结束时返回 200
+pub async fn trigger_webhook(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(query): Query<TriggerWebhookQuery>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ExecutionResponse>)> {
+    // 空请求体视为 null，方便不带数据的 git hook 调用
+    let payload = if body.trim_ascii().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| AppError::Webhook(format!("Invalid JSON body: {}", e)))?
+    };
+
+    let (webhook, execution) = state.webhook_service.trigger(&token, &payload).await?;
+    if !query.wait.unwrap_or(webhook.wait_for_result) {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(ExecutionResponse::from(execution)),
+        ));
+    }
+
+    let timeout_ms = query.timeout_ms.unwrap_or(MAX_WAIT_MS).min(MAX_WAIT_MS);
+    let execution = state
+        .execution_service
+        .wait_for_states(&execution.id, &SETTLED_STATUSES, timeout_ms)
+        .await?;
+    let status = if SETTLED_STATUSES.contains(&execution.status) {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    };
+    Ok((status, Json(ExecutionResponse::from(execution))))
+}
@@ -0,0 +1,102 @@
+use crate::api::dto::workflow::{
+    SaveWorkflowRequest, WorkflowResponse, WorkflowRunResponse, WorkflowRunsListResponse,
+    WorkflowsListResponse,
+};
+use crate::api::routes::AppState;
+use crate::error::Result;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+pub async fn list_workflows(State(state): State<AppState>) -> Result<Json<WorkflowsListResponse>> {
+    let workflows = state.workflow_service.list_workflows().await?;
+    let data = workflows
+        .into_iter()
+        .map(WorkflowResponse::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(WorkflowsListResponse { data }))
+}
+
+pub async fn get_workflow(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowResponse>> {
+    let workflow = state.workflow_service.get_workflow(&id).await?;
+    Ok(Json(WorkflowResponse::try_from(workflow)?))
+}
+
+pub async fn create_workflow(
+    State(state): State<AppState>,
+    Json(req): Json<SaveWorkflowRequest>,
+) -> Result<(StatusCode, Json<WorkflowResponse>)> {
+    let workflow = state
+        .workflow_service
+        .create_workflow(req.name, req.description, req.steps, req.failure_policy)
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(WorkflowResponse::try_from(workflow)?),
+    ))
+}
+
+pub async fn update_workflow(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<SaveWorkflowRequest>,
+) -> Result<Json<WorkflowResponse>> {
+    let workflow = state
+        .workflow_service
+        .update_workflow(
+            &id,
+            req.name,
+            req.description,
+            req.steps,
+            req.failure_policy,
+        )
+        .await?;
+    Ok(Json(WorkflowResponse::try_from(workflow)?))
+}
+
+pub async fn delete_workflow(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.workflow_service.delete_workflow(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn start_workflow_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<WorkflowRunResponse>)> {
+    let run = state.workflow_service.start_run(&id).await?;
+    Ok((StatusCode::CREATED, Json(WorkflowRunResponse::from(run))))
+}
+
+pub async fn list_workflow_runs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowRunsListResponse>> {
+    let runs = state.workflow_service.list_runs(&id).await?;
+    Ok(Json(WorkflowRunsListResponse {
+        data: runs.into_iter().map(WorkflowRunResponse::from).collect(),
+    }))
+}
+
+pub async fn get_workflow_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowRunResponse>> {
+    let run = state.workflow_service.get_run(&id).await?;
+    Ok(Json(WorkflowRunResponse::from(run)))
+}
+
+pub async fn stop_workflow_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowRunResponse>> {
+    let run = state.workflow_service.stop_run(&id).await?;
+    Ok(Json(WorkflowRunResponse::from(run)))
+}
@@ -3,4 +3,4 @@ pub mod handlers;
 pub mod middleware;
 pub mod routes;
 
-pub use routes::create_router;
+pub use routes::{AppState, create_router};
@@ -1,6 +1,12 @@
-use super::handlers::{execution, health, plugin, update};
+use super::handlers::{
+    email, execution, file_watch, health, notification, plugin, runtime, schedule, update, webhook,
+    workflow,
+};
 use super::middleware::cors::add_cors;
-use crate::services::{ExecutionService, PluginService, UpdateService};
+use crate::services::{
+    ExecutionService, FileWatcher, Mailer, Notifier, PluginService, RuntimeService,
+    SchedulerService, UpdateService, WebhookService, WorkflowService,
+};
 use axum::{
     Router,
     routing::{delete, get, post, put},
@@ -9,17 +15,18 @@ use axum::{
 #[derive(Clone)]
 pub struct AppState {
     pub plugin_service: PluginService,
+    pub runtime_service: RuntimeService,
     pub execution_service: ExecutionService,
     pub update_service: UpdateService,
+    pub workflow_service: WorkflowService,
+    pub scheduler: SchedulerService,
+    pub webhook_service: WebhookService,
+    pub file_watcher: FileWatcher,
+    pub notifier: Notifier,
+    pub mailer: Mailer,
 }
 
-pub fn create_router(plugin_service: PluginService, execution_service: ExecutionService) -> Router {
-    let state = AppState {
-        plugin_service,
-        execution_service,
-        update_service: UpdateService::new(),
-    };
-
+pub fn create_router(state: AppState) -> Router {
     let api_routes = Router::new()
         // Health check
         .route("/health", get(health::health_check))
@@ -31,6 +38,8 @@ pub fn create_router(plugin_service: PluginService, execution_service: Execution
         .route("/api/plugins/{id}", put(plugin::update_plugin))
         .route("/api/plugins/{id}/enable", put(plugin::enable_plugin))
         .route("/api/plugins/{id}/disable", put(plugin::disable_plugin))
+        // Runtimes
+        .route("/api/runtimes", get(runtime::list_runtimes))
         // Execution
         .route("/api/plugins/{id}/prepare", post(execution::prepare_plugin))
         .route("/api/plugins/{id}/execute", post(execution::execute_plugin))
@@ -38,9 +47,125 @@ pub fn create_router(plugin_service: PluginService, execution_service: Execution
             "/api/executions/{id}/apply",
             post(execution::apply_execution),
         )
+        .route(
+            "/api/executions/{id}/discard",
+            post(execution::discard_execution),
+        )
         .route("/api/executions", get(execution::list_executions))
+        .route("/api/executions", delete(execution::delete_executions))
         .route("/api/executions/{id}", get(execution::get_execution))
+        .route("/api/executions/{id}", delete(execution::delete_execution))
         .route("/api/executions/{id}/stop", put(execution::stop_execution))
+        .route("/api/executions/{id}/wait", get(execution::wait_execution))
+        .route(
+            "/api/executions/{id}/rerun",
+            post(execution::rerun_execution),
+        )
+        .route("/api/executions/{id}/input", post(execution::provide_input))
+        .route(
+            "/api/executions/{id}/logs",
+            get(execution::read_execution_logs),
+        )
+        .route(
+            "/api/executions/{id}/logs/stream",
+            get(execution::stream_execution_logs),
+        )
+        .route(
+            "/api/executions/{id}/artifacts",
+            get(execution::list_artifacts),
+        )
+        .route(
+            "/api/executions/{id}/artifacts/{*name}",
+            get(execution::download_artifact),
+        )
+        // Workflows
+        .route("/api/workflows", get(workflow::list_workflows))
+        .route("/api/workflows", post(workflow::create_workflow))
+        .route("/api/workflows/{id}", get(workflow::get_workflow))
+        .route("/api/workflows/{id}", put(workflow::update_workflow))
+        .route("/api/workflows/{id}", delete(workflow::delete_workflow))
+        .route(
+            "/api/workflows/{id}/runs",
+            get(workflow::list_workflow_runs),
+        )
+        .route(
+            "/api/workflows/{id}/runs",
+            post(workflow::start_workflow_run),
+        )
+        .route("/api/workflow-runs/{id}", get(workflow::get_workflow_run))
+        .route(
+            "/api/workflow-runs/{id}/stop",
+            put(workflow::stop_workflow_run),
+        )
+        // Schedules
+        .route("/api/schedules", get(schedule::list_schedules))
+        .route("/api/schedules", post(schedule::create_schedule))
+        .route("/api/schedules/{id}", get(schedule::get_schedule))
+        .route("/api/schedules/{id}", put(schedule::update_schedule))
+        .route("/api/schedules/{id}", delete(schedule::delete_schedule))
+        // Webhooks
+        .route("/api/webhooks", get(webhook::list_webhooks))
+        .route("/api/webhooks", post(webhook::create_webhook))
+        .route("/api/webhooks/{id}", get(webhook::get_webhook))
+        .route("/api/webhooks/{id}", put(webhook::update_webhook))
+        .route("/api/webhooks/{id}", delete(webhook::delete_webhook))
+        .route(
+            "/api/webhooks/{id}/rotate-token",
+            post(webhook::rotate_webhook_token),
+        )
+        .route("/api/hooks/{token}", post(webhook::trigger_webhook))
+        // File watches
+        .route("/api/watches", get(file_watch::list_watches))
+        .route("/api/watches", post(file_watch::create_watch))
+        .route("/api/watches/{id}", get(file_watch::get_watch))
+        .route("/api/watches/{id}", put(file_watch::update_watch))
+        .route("/api/watches/{id}", delete(file_watch::delete_watch))
+        // Notifications
+        .route("/api/notification-targets", get(notification::list_targets))
+        .route(
+            "/api/notification-targets",
+            post(notification::create_target),
+        )
+        .route(
+            "/api/notification-targets/{id}",
+            get(notification::get_target),
+        )
+        .route(
+            "/api/notification-targets/{id}",
+            put(notification::update_target),
+        )
+        .route(
+            "/api/notification-targets/{id}",
+            delete(notification::delete_target),
+        )
+        .route(
+            "/api/notification-deliveries",
+            get(notification::list_deliveries),
+        )
+        .route(
+            "/api/notification-deliveries/{id}",
+            get(notification::get_delivery),
+        )
+        .route(
+            "/api/notification-deliveries/{id}/retry",
+            post(notification::retry_delivery),
+        )
+        // Email
+        .route("/api/email-subscriptions", get(email::list_subscriptions))
+        .route("/api/email-subscriptions", post(email::create_subscription))
+        .route(
+            "/api/email-subscriptions/{id}",
+            get(email::get_subscription),
+        )
+        .route(
+            "/api/email-subscriptions/{id}",
+            put(email::update_subscription),
+        )
+        .route(
+            "/api/email-subscriptions/{id}",
+            delete(email::delete_subscription),
+        )
+        .route("/api/email-subscriptions/{id}/test", post(email::send_test))
         // Update
         .route("/api/update", post(update::stage_update))
         .with_state(state);
@@ -1,3 +1,4 @@
+use crate::models::{EmailConfig, JsPackageManager, LogLimits, OrphanPolicy, RetentionPolicy};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -7,7 +8,28 @@ pub struct Config {
     pub database_url: String,
     pub host: String,
     pub port: u16,
+    pub public_url: Option<String>,
     pub uv_path: Option<PathBuf>,
+    // 打包在安装目录下的运行时程序，未设置时从 PATH 查找
+    pub python_path: Option<PathBuf>,
+    pub node_path: Option<PathBuf>,
+    pub deno_path: Option<PathBuf>,
+    pub bun_path: Option<PathBuf>,
+    pub shell_path: Option<PathBuf>,
+    pub js_package_manager: JsPackageManager,
+    pub prepare_timeout_seconds: Option<u64>,
+    pub apply_timeout_seconds: Option<u64>,
+    pub max_concurrent_executions: Option<usize>,
+    pub orphan_policy: OrphanPolicy,
+    pub max_log_bytes: Option<usize>,
+    pub log_segment_bytes: Option<u64>,
+    pub max_log_segments: Option<usize>,
+    // 默认不清理执行记录。config.json 中的 retention_max_age_days、
+    // retention_max_executions_per_plugin、retention_failed_max_age_days 任一项开启清理，
+    // 清理会一并删除执行的日志文件与产物
+    pub retention: RetentionPolicy,
+    // 未设置时不发送邮件
+    pub email: Option<EmailConfig>,
 }
 
 impl Default for Config {
@@ -15,11 +37,28 @@ impl Default for Config {
         let database_url = crate::paths::data_dir()
             .map(|dir| format!("sqlite:{}", dir.join("anthill.db").display()))
             .unwrap_or_else(|_| "sqlite:anthill.db".to_string());
+        let log_limits = LogLimits::default();
         Self {
             database_url,
             host: "127.0.0.1".to_string(),
             port: 6701,
+            public_url: None,
             uv_path: None,
+            python_path: None,
+            node_path: None,
+            deno_path: None,
+            bun_path: None,
+            shell_path: None,
+            js_package_manager: JsPackageManager::default(),
+            prepare_timeout_seconds: Some(300),
+            apply_timeout_seconds: None,
+            max_concurrent_executions: None,
+            orphan_policy: OrphanPolicy::Kill,
+            max_log_bytes: log_limits.max_db_bytes,
+            log_segment_bytes: log_limits.segment_bytes,
+            max_log_segments: log_limits.max_segments,
+            retention: RetentionPolicy::default(),
+            email: None,
         }
     }
 }
@@ -45,10 +84,17 @@ impl Config {
         }
 
         config.normalize_database_url()?;
-        config.normalize_uv_path()?;
+        config.normalize_program_paths()?;
+        config.validate_email()?;
         Ok(config)
     }
 
+    pub fn base_url(&self) -> String {
+        self.public_url
+            .clone()
+            .unwrap_or_else(|| format!("http://{}:{}", self.host, self.port))
+    }
+
     fn from_conf_file() -> Result<Option<FileConfig>> {
         let path = crate::paths::conf_dir()?.join("config.json");
         if !path.is_file() {
@@ -72,9 +118,81 @@ impl Config {
         if let Some(port) = file_config.port {
             self.port = port;
         }
+        if let Some(public_url) = file_config.public_url {
+            self.public_url = Some(public_url);
+        }
         if let Some(uv_path) = file_config.uv_path {
             self.uv_path = Some(PathBuf::from(uv_path));
         }
+        if let Some(python_path) = file_config.python_path {
+            self.python_path = Some(PathBuf::from(python_path));
+        }
+        if let Some(node_path) = file_config.node_path {
+            self.node_path = Some(PathBuf::from(node_path));
+        }
+        if let Some(deno_path) = file_config.deno_path {
+            self.deno_path = Some(PathBuf::from(deno_path));
+        }
+        if let Some(bun_path) = file_config.bun_path {
+            self.bun_path = Some(PathBuf::from(bun_path));
+        }
+        if let Some(shell_path) = file_config.shell_path {
+            self.shell_path = Some(PathBuf::from(shell_path));
+        }
+        if let Some(manager) = file_config.js_package_manager {
+            self.js_package_manager = manager;
+        }
+        // 0 表示不限制执行时长
+        if let Some(seconds) = file_config.prepare_timeout_seconds {
+            self.prepare_timeout_seconds = (seconds > 0).then_some(seconds);
+        }
+        if let Some(seconds) = file_config.apply_timeout_seconds {
+            self.apply_timeout_seconds = (seconds > 0).then_some(seconds);
+        }
+        // 默认不限制并发执行数，设置为 0 同样表示不限制
+        if let Some(limit) = file_config.max_concurrent_executions {
+            self.max_concurrent_executions = (limit > 0).then_some(limit);
+        }
+        if let Some(policy) = file_config.orphan_policy {
+            self.orphan_policy = policy;
+        }
+        // 0 表示数据库中保存完整输出 / 日志文件不轮转 / 保留全部日志文件
+        if let Some(bytes) = file_config.max_log_bytes {
+            self.max_log_bytes = (bytes > 0).then_some(bytes);
+        }
+        if let Some(bytes) = file_config.log_segment_bytes {
+            self.log_segment_bytes = (bytes > 0).then_some(bytes);
+        }
+        if let Some(count) = file_config.max_log_segments {
+            self.max_log_segments = (count > 0).then_some(count);
+        }
+        // 未设置或为 0 表示不按该条件清理执行记录
+        if let Some(days) = file_config.retention_max_age_days {
+            self.retention.max_age_days = (days > 0).then_some(days);
+        }
+        if let Some(count) = file_config.retention_max_executions_per_plugin {
+            self.retention.max_executions_per_plugin = (count > 0).then_some(count);
+        }
+        if let Some(days) = file_config.retention_failed_max_age_days {
+            self.retention.failed_max_age_days = (days > 0).then_some(days);
+        }
+        if let Some(smtp_host) = file_config.smtp_host {
+            // 未指定端口时 STARTTLS 用 587，明文用 25
+            let smtp_starttls = file_config.smtp_starttls.unwrap_or(true);
+            let smtp_port = file_config
+                .smtp_port
+                .unwrap_or(if smtp_starttls { 587 } else { 25 });
+            self.email = Some(EmailConfig {
+                smtp_host,
+                smtp_port,
+                smtp_starttls,
+                smtp_username: file_config.smtp_username,
+                smtp_password: file_config.smtp_password,
+                from: file_config.email_from.unwrap_or_default(),
+                subject_template: file_config.email_subject_template,
+                body_template: file_config.email_body_template,
+            });
+        }
     }
 
     fn normalize_database_url(&mut self) -> Result<()> {
@@ -107,29 +225,58 @@ impl Config {
         Ok(())
     }
 
-    fn normalize_uv_path(&mut self) -> Result<()> {
-        let Some(path) = self.uv_path.as_ref() else {
-            return Ok(());
-        };
+    fn normalize_program_paths(&mut self) -> Result<()> {
+        for (key, path) in [
+            ("uv_path", &mut self.uv_path),
+            ("python_path", &mut self.python_path),
+            ("node_path", &mut self.node_path),
+            ("deno_path", &mut self.deno_path),
+            ("bun_path", &mut self.bun_path),
+            ("shell_path", &mut self.shell_path),
+        ] {
+            if let Some(relative) = path.take() {
+                *path = Some(Self::normalize_program_path(key, &relative)?);
+            }
+        }
+        Ok(())
+    }
 
+    // 配置中的程序路径相对于安装目录
+    fn normalize_program_path(key: &str, path: &Path) -> Result<PathBuf> {
         let path_str = path.to_string_lossy();
         if path_str.trim().is_empty() {
-            anyhow::bail!("uv_path in config cannot be empty");
+            anyhow::bail!("{} in config cannot be empty", key);
         }
 
         if path.is_absolute() {
-            anyhow::bail!("uv_path must be relative to install root");
+            anyhow::bail!("{} must be relative to install root", key);
         }
 
         if path
             .components()
             .any(|component| matches!(component, std::path::Component::ParentDir))
         {
-            anyhow::bail!("uv_path cannot contain '..'");
+            anyhow::bail!("{} cannot contain '..'", key);
         }
 
         let root = crate::paths::install_root()?;
-        self.uv_path = Some(root.join(path));
+        Ok(root.join(path))
+    }
+
+    fn validate_email(&self) -> Result<()> {
+        let Some(email) = self.email.as_ref() else {
+            return Ok(());
+        };
+
+        if email.smtp_host.trim().is_empty() {
+            anyhow::bail!("smtp_host in config cannot be empty");
+        }
+        if email.from.trim().is_empty() {
+            anyhow::bail!("email_from is required when smtp_host is set");
+        }
+        if email.smtp_password.is_some() && email.smtp_username.is_none() {
+            anyhow::bail!("smtp_password requires smtp_username");
+        }
         Ok(())
     }
 }
@@ -139,5 +286,30 @@ struct FileConfig {
     database_url: Option<String>,
     host: Option<String>,
     port: Option<u16>,
+    public_url: Option<String>,
     uv_path: Option<String>,
+    python_path: Option<String>,
+    node_path: Option<String>,
+    deno_path: Option<String>,
+    bun_path: Option<String>,
+    shell_path: Option<String>,
+    js_package_manager: Option<JsPackageManager>,
+    prepare_timeout_seconds: Option<u64>,
+    apply_timeout_seconds: Option<u64>,
+    max_concurrent_executions: Option<usize>,
+    orphan_policy: Option<OrphanPolicy>,
+    max_log_bytes: Option<usize>,
+    log_segment_bytes: Option<u64>,
+    max_log_segments: Option<usize>,
+    retention_max_age_days: Option<u64>,
+    retention_max_executions_per_plugin: Option<usize>,
+    retention_failed_max_age_days: Option<u64>,
+    smtp_host: Option<String>,
+    smtp_port: Option<u16>,
+    smtp_starttls: Option<bool>,
+    smtp_username: Option<String>,
+    smtp_password: Option<String>,
+    email_from: Option<String>,
+    email_subject_template: Option<String>,
+    email_body_template: Option<String>,
 }
@@ -20,9 +20,57 @@ pub enum AppError {
     #[error("Execution not found: {0}")]
     ExecutionNotFound(String),
 
+    #[error("Artifact not found: {0}")]
+    ArtifactNotFound(String),
+
     #[error("Execution error: {0}")]
     Execution(String),
 
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(String),
+
+    #[error("Workflow run not found: {0}")]
+    WorkflowRunNotFound(String),
+
+    #[error("Workflow error: {0}")]
+    Workflow(String),
+
+    #[error("Schedule not found: {0}")]
+    ScheduleNotFound(String),
+
+    #[error("Schedule error: {0}")]
+    Schedule(String),
+
+    #[error("Webhook not found: {0}")]
+    WebhookNotFound(String),
+
+    #[error("Webhook is disabled")]
+    WebhookDisabled,
+
+    #[error("Webhook error: {0}")]
+    Webhook(String),
+
+    #[error("File watch not found: {0}")]
+    WatchNotFound(String),
+
+    #[error("File watch error: {0}")]
+    Watch(String),
+
+    #[error("Notification target not found: {0}")]
+    NotificationTargetNotFound(String),
+
+    #[error("Notification delivery not found: {0}")]
+    DeliveryNotFound(String),
+
+    #[error("Notification error: {0}")]
+    Notification(String),
+
+    #[error("Email subscription not found: {0}")]
+    EmailSubscriptionNotFound(String),
+
+    #[error("Email error: {0}")]
+    Email(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -51,7 +99,49 @@ impl IntoResponse for AppError {
                 StatusCode::NOT_FOUND,
                 format!("Execution '{}' not found", id),
             ),
+            AppError::ArtifactNotFound(name) => (
+                StatusCode::NOT_FOUND,
+                format!("Artifact '{}' not found", name),
+            ),
             AppError::Execution(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::WorkflowNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Workflow '{}' not found", id),
+            ),
+            AppError::WorkflowRunNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Workflow run '{}' not found", id),
+            ),
+            AppError::Workflow(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::ScheduleNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Schedule '{}' not found", id),
+            ),
+            AppError::Schedule(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::WebhookNotFound(id) => {
+                (StatusCode::NOT_FOUND, format!("Webhook '{}' not found", id))
+            }
+            AppError::WebhookDisabled => (StatusCode::FORBIDDEN, "Webhook is disabled".to_string()),
+            AppError::Webhook(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::WatchNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("File watch '{}' not found", id),
+            ),
+            AppError::Watch(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::NotificationTargetNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Notification target '{}' not found", id),
+            ),
+            AppError::DeliveryNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Notification delivery '{}' not found", id),
+            ),
+            AppError::Notification(e) => (StatusCode::BAD_REQUEST, e),
+            AppError::EmailSubscriptionNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("Email subscription '{}' not found", id),
+            ),
+            AppError::Email(e) => (StatusCode::BAD_REQUEST, e),
             AppError::Io(e) => {
                 tracing::error!("IO error: {}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
@@ -0,0 +1,59 @@
+use super::{PluginExecutor, ensure_program, spawn_piped};
+use crate::error::{AppError, Result};
+use crate::models::Plugin;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Clone)]
+pub struct BunExecutor {
+    bun_path: String,
+}
+
+impl BunExecutor {
+    pub fn new(bun_path: Option<String>) -> Self {
+        Self {
+            bun_path: bun_path.unwrap_or_else(|| "bun".to_string()),
+        }
+    }
+}
+
+impl Default for BunExecutor {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl PluginExecutor for BunExecutor {
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>> {
+        Box::pin(async move {
+            let script_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
+            if !script_path.is_file() {
+                return Err(AppError::Execution(format!(
+                    "Entry point not found: {}",
+                    script_path.display()
+                )));
+            }
+
+            // Build the command
+            let mut cmd = tokio::process::Command::new(&self.bun_path);
+            cmd.arg("run").arg(&script_path);
+
+            spawn_piped(cmd, args, env, work_dir)
+        })
+    }
+
+    fn check_available(&self) -> Result<()> {
+        ensure_program("bun", &self.bun_path)
+    }
+
+    fn program(&self) -> Option<&str> {
+        Some(&self.bun_path)
+    }
+}
@@ -0,0 +1,103 @@
+use super::{PluginExecutor, ensure_program, spawn_piped};
+use crate::error::{AppError, Result};
+use crate::models::{DenoPermissions, PermissionScope, Plugin};
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Clone)]
+pub struct DenoExecutor {
+    deno_path: String,
+}
+
+impl DenoExecutor {
+    pub fn new(deno_path: Option<String>) -> Self {
+        Self {
+            deno_path: deno_path.unwrap_or_else(|| "deno".to_string()),
+        }
+    }
+
+    // 声明的权限，加上每个插件都需要的：自身文件、工作目录和传入的环境变量
+    fn permission_flags(
+        permissions: &DenoPermissions,
+        plugin: &Plugin,
+        env: &HashMap<String, String>,
+        work_dir: &Path,
+    ) -> Vec<String> {
+        let work_dir = work_dir.to_string_lossy().to_string();
+        let mut env_names: Vec<String> = env.keys().cloned().collect();
+        env_names.sort();
+
+        [
+            (
+                "read",
+                &permissions.read,
+                vec![plugin.plugin_path.clone(), work_dir.clone()],
+            ),
+            ("write", &permissions.write, vec![work_dir]),
+            ("env", &permissions.env, env_names),
+            ("net", &permissions.net, Vec::new()),
+            ("run", &permissions.run, Vec::new()),
+            ("sys", &permissions.sys, Vec::new()),
+        ]
+        .into_iter()
+        .filter_map(|(name, scope, mut allowed)| match scope {
+            PermissionScope::All(true) => Some(format!("--allow-{}", name)),
+            PermissionScope::All(false) | PermissionScope::Only(_) => {
+                if let PermissionScope::Only(extra) = scope {
+                    allowed.extend(extra.iter().cloned());
+                }
+                (!allowed.is_empty()).then(|| format!("--allow-{}={}", name, allowed.join(",")))
+            }
+        })
+        .collect()
+    }
+}
+
+impl Default for DenoExecutor {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl PluginExecutor for DenoExecutor {
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>> {
+        Box::pin(async move {
+            let script_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
+            if !script_path.is_file() {
+                return Err(AppError::Execution(format!(
+                    "Entry point not found: {}",
+                    script_path.display()
+                )));
+            }
+            let permissions: DenoPermissions = match plugin.permissions.as_deref() {
+                Some(raw) => serde_json::from_str(raw).map_err(|e| {
+                    AppError::Execution(format!("Invalid plugin permissions: {}", e))
+                })?,
+                None => DenoPermissions::default(),
+            };
+
+            // Build the command; stdin answers input requests, so Deno must never prompt
+            let mut cmd = tokio::process::Command::new(&self.deno_path);
+            cmd.arg("run").arg("--no-prompt");
+            cmd.args(Self::permission_flags(&permissions, plugin, &env, work_dir));
+            cmd.arg(&script_path);
+
+            spawn_piped(cmd, args, env, work_dir)
+        })
+    }
+
+    fn check_available(&self) -> Result<()> {
+        ensure_program("deno", &self.deno_path)
+    }
+
+    fn program(&self) -> Option<&str> {
+        Some(&self.deno_path)
+    }
+}
@@ -0,0 +1,32 @@
+use super::{PluginExecutor, spawn_piped};
+use crate::error::{AppError, Result};
+use crate::models::Plugin;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Clone, Default)]
+pub struct ExecutableExecutor;
+
+impl PluginExecutor for ExecutableExecutor {
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>> {
+        Box::pin(async move {
+            let program_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
+            if !program_path.is_file() {
+                return Err(AppError::Execution(format!(
+                    "Entry point not found: {}",
+                    program_path.display()
+                )));
+            }
+
+            let cmd = tokio::process::Command::new(&program_path);
+            spawn_piped(cmd, args, env, work_dir)
+        })
+    }
+}
@@ -1,20 +1,187 @@
+pub mod bun_executor;
+pub mod deno_executor;
+pub mod executable_executor;
 pub mod node_executor;
 pub mod python_executor;
+pub mod shell_executor;
 
+pub use bun_executor::BunExecutor;
+pub use deno_executor::DenoExecutor;
+pub use executable_executor::ExecutableExecutor;
 pub use node_executor::NodeExecutor;
 pub use python_executor::PythonExecutor;
+pub use shell_executor::ShellExecutor;
 
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::Plugin;
+use futures_util::future::BoxFuture;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
-pub(crate) trait PluginExecutor {
-    async fn execute(
-        &self,
-        plugin: &Plugin,
+// 对象安全，启动时可以把运行时注册到 ExecutorRegistry
+pub trait PluginExecutor: Send + Sync {
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
         args: Vec<String>,
         env: HashMap<String, String>,
-        work_dir: &Path,
-    ) -> Result<(u32, tokio::process::Child)>;
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>>;
+
+    // 每次执行前检查，运行时不可用时返回可读的错误
+    fn check_available(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn program(&self) -> Option<&str> {
+        None
+    }
+
+    fn version_arg(&self) -> Option<&str> {
+        Some("--version")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuntimePaths {
+    pub python: Option<PathBuf>,
+    pub node: Option<PathBuf>,
+    pub deno: Option<PathBuf>,
+    pub bun: Option<PathBuf>,
+    pub shell: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct ExecutorRegistry {
+    executors: Arc<RwLock<HashMap<String, Arc<dyn PluginExecutor>>>>,
+}
+
+impl ExecutorRegistry {
+    pub fn empty() -> Self {
+        Self {
+            executors: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_builtins() -> Self {
+        Self::with_runtime_paths(RuntimePaths::default())
+    }
+
+    pub fn with_runtime_paths(paths: RuntimePaths) -> Self {
+        let to_string = |path: Option<PathBuf>| path.map(|path| path.to_string_lossy().to_string());
+        let registry = Self::empty();
+        registry.register("python", PythonExecutor::new(to_string(paths.python)));
+        registry.register("node", NodeExecutor::new(to_string(paths.node)));
+        registry.register("deno", DenoExecutor::new(to_string(paths.deno)));
+        registry.register("bun", BunExecutor::new(to_string(paths.bun)));
+        registry.register("shell", ShellExecutor::new(to_string(paths.shell)));
+        registry.register("executable", ExecutableExecutor);
+        registry
+    }
+
+    pub fn register(&self, runtime: impl Into<String>, executor: impl PluginExecutor + 'static) {
+        self.executors
+            .write()
+            .unwrap()
+            .insert(runtime.into(), Arc::new(executor));
+    }
+
+    pub fn get(&self, runtime: &str) -> Option<Arc<dyn PluginExecutor>> {
+        self.executors.read().unwrap().get(runtime).cloned()
+    }
+
+    pub fn contains(&self, runtime: &str) -> bool {
+        self.executors.read().unwrap().contains_key(runtime)
+    }
+
+    pub fn list(&self) -> Vec<(String, Arc<dyn PluginExecutor>)> {
+        let mut runtimes: Vec<_> = self
+            .executors
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, executor)| (name.clone(), executor.clone()))
+            .collect();
+        runtimes.sort_by(|a, b| a.0.cmp(&b.0));
+        runtimes
+    }
+}
+
+impl Default for ExecutorRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+// 与启动进程时的查找方式一致：带路径的直接使用，否则在 PATH 中查找
+pub(crate) fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|ext| ext.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?).find_map(|dir| {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", program, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+pub(crate) fn ensure_program(runtime: &str, program: &str) -> Result<()> {
+    if find_program(program).is_none() {
+        return Err(AppError::Execution(format!(
+            "Runtime '{}' is not available: '{}' was not found, install it or add it to PATH",
+            runtime, program
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn spawn_piped(
+    mut cmd: tokio::process::Command,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    work_dir: &Path,
+) -> Result<(u32, tokio::process::Child)> {
+    cmd.args(args);
+    cmd.envs(env);
+    cmd.current_dir(work_dir);
+
+    // Capture stdout and stderr, stdin carries answers to input requests
+    cmd.stdin(std::process::Stdio::piped());
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+    isolate_process_group(&mut cmd);
+
+    let child = cmd.spawn()?;
+    let pid = child
+        .id()
+        .ok_or_else(|| AppError::Execution("Failed to get process ID".to_string()))?;
+    Ok((pid, child))
+}
+
+// 插件在独立的进程组中启动，停止时可以向整棵进程树发信号
+fn isolate_process_group(cmd: &mut tokio::process::Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
 }
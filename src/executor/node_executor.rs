@@ -1,6 +1,7 @@
-use super::PluginExecutor;
+use super::{PluginExecutor, ensure_program, spawn_piped};
 use crate::error::{AppError, Result};
 use crate::models::Plugin;
+use futures_util::future::BoxFuture;
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -24,45 +25,35 @@ impl Default for NodeExecutor {
 }
 
 impl PluginExecutor for NodeExecutor {
-    async fn execute(
-        &self,
-        plugin: &Plugin,
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
         args: Vec<String>,
         env: HashMap<String, String>,
-        work_dir: &Path,
-    ) -> Result<(u32, tokio::process::Child)> {
-        let script_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
-        if !script_path.is_file() {
-            return Err(AppError::Execution(format!(
-                "Entry point not found: {}",
-                script_path.display()
-            )));
-        }
-
-        // Build the command
-        let mut cmd = tokio::process::Command::new(&self.node_path);
-        cmd.arg(&script_path);
-        cmd.current_dir(work_dir);
-
-        for arg in args {
-            cmd.arg(arg);
-        }
-
-        // Set environment variables
-        for (key, value) in env {
-            cmd.env(key, value);
-        }
-
-        // Capture stdout and stderr
-        cmd.stdout(std::process::Stdio::piped());
-        cmd.stderr(std::process::Stdio::piped());
-
-        let child = cmd.spawn()?;
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>> {
+        Box::pin(async move {
+            let script_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
+            if !script_path.is_file() {
+                return Err(AppError::Execution(format!(
+                    "Entry point not found: {}",
+                    script_path.display()
+                )));
+            }
+
+            // Build the command
+            let mut cmd = tokio::process::Command::new(&self.node_path);
+            cmd.arg(&script_path);
+
+            spawn_piped(cmd, args, env, work_dir)
+        })
+    }
 
-        let pid = child
-            .id()
-            .ok_or_else(|| AppError::Execution("Failed to get process ID".to_string()))?;
+    fn check_available(&self) -> Result<()> {
+        ensure_program("node", &self.node_path)
+    }
 
-        Ok((pid, child))
+    fn program(&self) -> Option<&str> {
+        Some(&self.node_path)
     }
 }
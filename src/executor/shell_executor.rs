@@ -0,0 +1,68 @@
+use super::{PluginExecutor, spawn_piped};
+use crate::error::{AppError, Result};
+use crate::models::Plugin;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Clone)]
+pub struct ShellExecutor {
+    shell_path: String,
+}
+
+impl ShellExecutor {
+    pub fn new(shell_path: Option<String>) -> Self {
+        Self {
+            shell_path: shell_path.unwrap_or_else(|| "sh".to_string()),
+        }
+    }
+}
+
+impl Default for ShellExecutor {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl PluginExecutor for ShellExecutor {
+    fn execute<'a>(
+        &'a self,
+        plugin: &'a Plugin,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        work_dir: &'a Path,
+    ) -> BoxFuture<'a, Result<(u32, tokio::process::Child)>> {
+        Box::pin(async move {
+            let script_path = Path::new(&plugin.plugin_path).join(&plugin.entry_point);
+            if !script_path.is_file() {
+                return Err(AppError::Execution(format!(
+                    "Entry point not found: {}",
+                    script_path.display()
+                )));
+            }
+
+            // 解释器可以带参数，例如 "/usr/bin/env bash"
+            let interpreter = plugin.interpreter.as_deref().unwrap_or(&self.shell_path);
+            let mut words = interpreter.split_whitespace();
+            let program = words.next().ok_or_else(|| {
+                AppError::Execution(format!("Invalid interpreter: '{}'", interpreter))
+            })?;
+
+            // Build the command
+            let mut cmd = tokio::process::Command::new(program);
+            cmd.args(words);
+            cmd.arg(&script_path);
+
+            spawn_piped(cmd, args, env, work_dir)
+        })
+    }
+
+    fn program(&self) -> Option<&str> {
+        Some(&self.shell_path)
+    }
+
+    // dash 等 sh 实现没有查询版本的参数
+    fn version_arg(&self) -> Option<&str> {
+        None
+    }
+}
@@ -15,9 +15,20 @@ mod services;
 mod windows_tray;
 
 use crate::config::Config;
-use crate::repository::{ExecutionRepository, PluginRepository, establish_connection};
-use crate::services::{ExecutionService, PluginService, UpdateService};
-use api::create_router;
+use crate::executor::{ExecutorRegistry, RuntimePaths};
+use crate::models::LogLimits;
+use crate::repository::{
+    EmailSubscriptionRepository, ExecutionRepository, FileWatchRepository, NotificationRepository,
+    PluginRepository, ScheduleRepository, WebhookRepository, WorkflowRepository,
+    establish_connection,
+};
+use crate::services::execution_service::ExecutionLimits;
+use crate::services::retention::RetentionService;
+use crate::services::{
+    ExecutionService, FileWatcher, Mailer, Notifier, PluginService, RuntimeService,
+    SchedulerService, UpdateService, WebhookService, WorkflowService,
+};
+use api::{AppState, create_router};
 use std::future::Future;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -78,14 +89,96 @@ where
 
     // Initialize repositories
     let plugin_repo = PluginRepository::new(db_pool.clone());
-    let execution_repo = ExecutionRepository::new(db_pool);
+    let execution_repo = ExecutionRepository::new(db_pool.clone());
+    let workflow_repo = WorkflowRepository::new(db_pool.clone());
+    let schedule_repo = ScheduleRepository::new(db_pool.clone());
+    let webhook_repo = WebhookRepository::new(db_pool.clone());
+    let file_watch_repo = FileWatchRepository::new(db_pool.clone());
+    let notification_repo = NotificationRepository::new(db_pool.clone());
+    let email_subscription_repo = EmailSubscriptionRepository::new(db_pool);
+
+    RetentionService::new(execution_repo.clone(), config.retention.clone()).spawn_pruner();
 
     // Initialize services
-    let plugin_service = PluginService::new(plugin_repo.clone(), config.uv_path.clone());
-    let execution_service = ExecutionService::new(execution_repo, plugin_repo);
+    let executors = ExecutorRegistry::with_runtime_paths(RuntimePaths {
+        python: config.python_path.clone(),
+        node: config.node_path.clone(),
+        deno: config.deno_path.clone(),
+        bun: config.bun_path.clone(),
+        shell: config.shell_path.clone(),
+    });
+    let runtime_service = RuntimeService::new(executors.clone(), config.uv_path.clone());
+    let plugin_service = PluginService::new(
+        plugin_repo.clone(),
+        config.uv_path.clone(),
+        config.python_path.clone(),
+        config.js_package_manager,
+        executors.clone(),
+    );
+    let limits = ExecutionLimits {
+        prepare_timeout_seconds: config.prepare_timeout_seconds,
+        apply_timeout_seconds: config.apply_timeout_seconds,
+        max_concurrent_executions: config.max_concurrent_executions,
+        logs: LogLimits {
+            max_db_bytes: config.max_log_bytes,
+            segment_bytes: config.log_segment_bytes,
+            max_segments: config.max_log_segments,
+        },
+    };
+    let execution_service =
+        ExecutionService::new(execution_repo, plugin_repo.clone(), executors, limits);
+    // 先订阅状态变化，恢复孤儿执行时产生的状态也会通知
+    let notifier = Notifier::new(
+        notification_repo,
+        plugin_repo.clone(),
+        execution_service.clone(),
+        config.base_url(),
+    );
+    notifier.spawn();
+    let mailer = Mailer::new(
+        email_subscription_repo,
+        plugin_repo.clone(),
+        execution_service.clone(),
+        config.email.clone(),
+        config.base_url(),
+    )?;
+    mailer.spawn();
+    if let Err(err) = execution_service
+        .recover_orphans(config.orphan_policy)
+        .await
+    {
+        tracing::error!("Failed to recover orphaned executions: {}", err);
+    }
+    execution_service.spawn_dispatcher();
+    execution_service.spawn_preview_sweeper();
+
+    let workflow_service =
+        WorkflowService::new(workflow_repo, plugin_repo, execution_service.clone());
+    if let Err(err) = workflow_service.recover_interrupted_runs().await {
+        tracing::error!("Failed to recover interrupted workflow runs: {}", err);
+    }
+
+    let scheduler = SchedulerService::new(schedule_repo, execution_service.clone());
+    scheduler.spawn_ticker();
+    let webhook_service = WebhookService::new(webhook_repo, execution_service.clone());
+    let file_watcher = FileWatcher::new(file_watch_repo, execution_service.clone());
+    if let Err(err) = file_watcher.start_all().await {
+        tracing::error!("Failed to start file watches: {}", err);
+    }
 
     // Create router
-    let app = create_router(plugin_service, execution_service);
+    let app = create_router(AppState {
+        plugin_service,
+        runtime_service,
+        execution_service,
+        update_service: UpdateService::new(),
+        workflow_service,
+        scheduler,
+        webhook_service,
+        file_watcher,
+        notifier,
+        mailer,
+    });
     let app = app.layer(TraceLayer::new_for_http());
 
     // Start server
@@ -0,0 +1,9 @@
+// 执行输出目录中保留下来的文件
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    // 相对输出目录的路径，使用 / 分隔
+    pub name: String,
+    pub size: u64,
+    pub content_type: String,
+    pub modified_at: Option<i64>,
+}
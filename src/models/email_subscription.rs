@@ -0,0 +1,38 @@
+use crate::models::ExecutionStatus;
+use serde::{Deserialize, Serialize};
+
+// 没有 plugin_id 的订阅适用于所有插件
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct EmailSubscription {
+    pub id: String,
+    pub email: String,
+    pub plugin_id: Option<String>,
+    pub outcome: EmailOutcome,
+    pub enabled: bool,
+    pub last_sent_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailOutcome {
+    #[default]
+    Failures = 0,
+    // 包括被停止和过期的执行
+    All = 1,
+}
+
+impl EmailOutcome {
+    pub fn matches(self, status: ExecutionStatus) -> bool {
+        match status {
+            ExecutionStatus::Failed | ExecutionStatus::TimedOut => true,
+            ExecutionStatus::Completed | ExecutionStatus::Stopped | ExecutionStatus::Expired => {
+                self == EmailOutcome::All
+            }
+            _ => false,
+        }
+    }
+}
@@ -11,10 +11,40 @@ pub struct Execution {
     pub stdout: Option<String>,
     pub stderr: Option<String>,
     pub preview_payload: Option<String>,
+    pub result: Option<String>,
+    pub error_message: Option<String>,
+    #[sqlx(flatten)]
+    pub progress: ExecutionProgress,
+    pub input_request: Option<String>,
+    pub params: Option<String>,
+    pub plugin_version: Option<String>,
+    pub entry_point: Option<String>,
+    pub rerun_of: Option<String>,
+    // 不是直接请求时记录触发来源，例如 schedule:<id>
+    pub triggered_by: Option<String>,
     pub confirm_token: Option<String>,
     pub expires_at: Option<i64>,
     pub started_at: i64,
     pub finished_at: Option<i64>,
+    // Pending 时在队列中的位置，不存入数据库
+    #[sqlx(skip)]
+    pub queue_position: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow, PartialEq)]
+pub struct ExecutionProgress {
+    #[sqlx(rename = "progress_percent")]
+    pub percent: Option<f64>,
+    #[sqlx(rename = "progress_step")]
+    pub step: Option<String>,
+    #[sqlx(rename = "progress_message")]
+    pub message: Option<String>,
+}
+
+impl ExecutionProgress {
+    pub fn is_empty(&self) -> bool {
+        self.percent.is_none() && self.step.is_none() && self.message.is_none()
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -34,4 +64,29 @@ pub enum ExecutionStatus {
     Completed = 4,
     Failed = 5,
     Stopped = 6,
+    TimedOut = 7,
+    AwaitingInput = 8,
+    Expired = 9,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionOrigin {
+    pub rerun_of: Option<String>,
+    pub triggered_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
 }
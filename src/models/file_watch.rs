@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FileWatch {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub path: String,
+    pub recursive: bool,
+    pub patterns: String,
+    pub events: String,
+    pub debounce_ms: i64,
+    // 接收变更路径的 file 或 directory 参数
+    pub path_param: String,
+    pub params: String,
+    pub enabled: bool,
+    pub last_triggered_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchEvent {
+    Created,
+    Modified,
+    Deleted,
+}
@@ -1,7 +1,31 @@
+pub mod artifact;
+pub mod email_subscription;
 pub mod execution;
+pub mod file_watch;
+pub mod notification;
 pub mod plugin;
+pub mod runtime;
+pub mod schedule;
+pub mod settings;
+pub mod webhook;
+pub mod workflow;
 
-pub use execution::{Execution, ExecutionPhase, ExecutionStatus};
+pub use artifact::Artifact;
+pub use email_subscription::{EmailOutcome, EmailSubscription};
+pub use execution::{
+    Execution, ExecutionOrigin, ExecutionPhase, ExecutionProgress, ExecutionStatus, LogStream,
+};
+pub use file_watch::{FileWatch, WatchEvent};
+pub use notification::{DEFAULT_EVENTS, DeliveryStatus, NotificationDelivery, NotificationTarget};
 pub use plugin::{
-    Plugin, PluginParamType, PluginParameter, PluginParameterGroup, PluginType, PythonDependencies,
+    DenoPermissions, JsPackageManager, PermissionScope, Plugin, PluginDependencies,
+    PluginParamType, PluginParameter, PluginParameterGroup, PluginType, PythonDependencies,
+};
+pub use runtime::RuntimeInfo;
+pub use schedule::{OverlapPolicy, Schedule};
+pub use settings::{EmailConfig, LogLimits, OrphanPolicy, RetentionPolicy};
+pub use webhook::Webhook;
+pub use workflow::{
+    FailurePolicy, RunCondition, StepRunStatus, Workflow, WorkflowRun, WorkflowRunStatus,
+    WorkflowStep, WorkflowStepRun,
 };
@@ -0,0 +1,50 @@
+use crate::models::ExecutionStatus;
+use serde::{Deserialize, Serialize};
+
+// 未指定 events 的通知目标订阅的状态
+pub const DEFAULT_EVENTS: [ExecutionStatus; 4] = [
+    ExecutionStatus::Completed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Stopped,
+    ExecutionStatus::PreviewReady,
+];
+
+// events 是状态的 JSON 数组，没有 plugin_id 的目标适用于所有插件
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotificationTarget {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    // X-AntHill-Signature 头的 HMAC-SHA256 密钥
+    pub secret: Option<String>,
+    pub plugin_id: Option<String>,
+    pub events: String,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending = 0,
+    Succeeded = 1,
+    Failed = 2,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotificationDelivery {
+    pub id: String,
+    pub target_id: String,
+    pub execution_id: String,
+    pub event: ExecutionStatus,
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<i64>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
@@ -9,6 +9,12 @@ pub struct Plugin {
     pub version: String,
     pub min_anthill_version: Option<String>,
     pub plugin_type: PluginType,
+    // 执行插件的运行时，旧插件为空时按类型取默认值
+    pub runtime: Option<String>,
+    // shell 插件的解释器，来自元数据或 shebang
+    pub interpreter: Option<String>,
+    // Deno 运行时的 DenoPermissions（JSON）
+    pub permissions: Option<String>,
     pub description: String,
     pub author: String,
     pub plugin_path: String,
@@ -19,6 +25,11 @@ pub struct Plugin {
     pub metadata: Option<String>,
     pub python_venv_path: Option<String>,
     pub python_dependencies: Option<String>,
+    // 随插件安装的 PluginDependencies（JSON）
+    pub dependencies: Option<String>,
+    pub prepare_timeout_seconds: Option<i64>,
+    pub apply_timeout_seconds: Option<i64>,
+    pub max_concurrency: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -28,6 +39,61 @@ pub struct Plugin {
 pub enum PluginType {
     Python = 0,
     JavaScript = 1,
+    Custom = 2,
+    Shell = 3,
+    Executable = 4,
+}
+
+impl PluginType {
+    pub fn default_runtime(self) -> Option<&'static str> {
+        match self {
+            Self::Python => Some("python"),
+            Self::JavaScript => Some("node"),
+            Self::Shell => Some("shell"),
+            Self::Executable => Some("executable"),
+            Self::Custom => None,
+        }
+    }
+}
+
+impl Plugin {
+    pub fn runtime_name(&self) -> Option<&str> {
+        self.runtime
+            .as_deref()
+            .or_else(|| self.plugin_type.default_runtime())
+    }
+}
+
+// 默认只能读插件目录、写工作目录、读取 AntHill 传入的环境变量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DenoPermissions {
+    #[serde(default)]
+    pub read: PermissionScope,
+    #[serde(default)]
+    pub write: PermissionScope,
+    #[serde(default)]
+    pub net: PermissionScope,
+    #[serde(default)]
+    pub env: PermissionScope,
+    #[serde(default)]
+    pub run: PermissionScope,
+    #[serde(default)]
+    pub sys: PermissionScope,
+}
+
+// true 表示全部允许，列表只允许其中的路径、主机或变量名
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PermissionScope {
+    All(bool),
+    Only(Vec<String>),
+}
+
+impl Default for PermissionScope {
+    fn default() -> Self {
+        Self::All(false)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -73,6 +139,15 @@ pub struct PluginParameter {
     pub extras: std::collections::BTreeMap<String, Value>,
 }
 
+impl PluginParameter {
+    pub fn is_secret(&self) -> bool {
+        self.extras
+            .get("secret")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginParameterGroup {
     pub id: String,
@@ -87,3 +162,74 @@ pub enum PythonDependencies {
     Requirements { path: String },
     Pyproject { path: String },
 }
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JsPackageManager {
+    #[default]
+    Npm,
+    Pnpm,
+    Bun,
+}
+
+impl JsPackageManager {
+    pub fn program(self) -> &'static str {
+        match self {
+            JsPackageManager::Npm => "npm",
+            JsPackageManager::Pnpm => "pnpm",
+            JsPackageManager::Bun => "bun",
+        }
+    }
+
+    pub fn lockfiles(self) -> &'static [&'static str] {
+        match self {
+            JsPackageManager::Npm => &["package-lock.json", "npm-shrinkwrap.json"],
+            JsPackageManager::Pnpm => &["pnpm-lock.yaml"],
+            JsPackageManager::Bun => &["bun.lock", "bun.lockb"],
+        }
+    }
+
+    // 有 lockfile 时按 lockfile 安装，否则不生成 lockfile
+    pub fn install_args(self, locked: bool) -> &'static [&'static str] {
+        match (self, locked) {
+            (JsPackageManager::Npm, true) => &["ci"],
+            (JsPackageManager::Npm, false) => &["install", "--no-package-lock"],
+            (JsPackageManager::Pnpm, true) => &["install", "--frozen-lockfile"],
+            (JsPackageManager::Pnpm, false) => &["install", "--no-lockfile"],
+            (JsPackageManager::Bun, true) => &["install", "--frozen-lockfile"],
+            (JsPackageManager::Bun, false) => &["install", "--no-save"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum PluginDependencies {
+    Requirements {
+        path: String,
+    },
+    Pyproject {
+        path: String,
+    },
+    PackageJson {
+        path: String,
+        lockfile: Option<String>,
+        // 没有 lockfile 时按未锁定的版本安装，依赖不可复现
+        #[serde(default)]
+        locked: bool,
+        manager: JsPackageManager,
+    },
+}
+
+impl From<&PythonDependencies> for PluginDependencies {
+    fn from(deps: &PythonDependencies) -> Self {
+        match deps {
+            PythonDependencies::Requirements { path } => {
+                PluginDependencies::Requirements { path: path.clone() }
+            }
+            PythonDependencies::Pyproject { path } => {
+                PluginDependencies::Pyproject { path: path.clone() }
+            }
+        }
+    }
+}
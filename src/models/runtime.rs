@@ -0,0 +1,8 @@
+#[derive(Debug)]
+pub struct RuntimeInfo {
+    pub name: String,
+    // 解析到的程序路径，运行时不需要程序或未找到时为空
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub available: bool,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub cron: Option<String>,
+    pub interval_seconds: Option<i64>,
+    pub timezone: String,
+    pub params: String,
+    pub enabled: bool,
+    pub overlap_policy: OverlapPolicy,
+    pub last_run_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    pub last_error: Option<String>,
+    pub next_run_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    #[default]
+    Skip = 0,
+    // 上一次执行结束后再运行，最多等待一次
+    Queue = 1,
+    Allow = 2,
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 启动时如何处理上次运行遗留的插件进程
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanPolicy {
+    // 终止进程树并把执行记为失败
+    #[default]
+    Kill,
+    // 继续运行 apply 阶段的进程，退出后记录结果
+    Adopt,
+}
+
+// 数据库与日志文件中保留多少输出
+#[derive(Debug, Clone)]
+pub struct LogLimits {
+    // 每个流写入 executions 表的字节数，超出时丢弃中间部分
+    pub max_db_bytes: Option<usize>,
+    // 日志文件达到该大小后轮转
+    pub segment_bytes: Option<u64>,
+    // 每个流保留的日志文件数，更早的会被删除
+    pub max_segments: Option<usize>,
+}
+
+impl Default for LogLimits {
+    fn default() -> Self {
+        Self {
+            max_db_bytes: Some(1024 * 1024),
+            segment_bytes: Some(8 * 1024 * 1024),
+            max_segments: Some(8),
+        }
+    }
+}
+
+// 已结束执行的保留期限，运行中的执行不会被清理
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub max_age_days: Option<u64>,
+    pub max_executions_per_plugin: Option<usize>,
+    // 失败和超时执行单独的保留天数，设置后它们也不计入 max_executions_per_plugin
+    pub failed_max_age_days: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_disabled(&self) -> bool {
+        self.max_age_days.is_none()
+            && self.max_executions_per_plugin.is_none()
+            && self.failed_max_age_days.is_none()
+    }
+}
+
+// config.json 中的 SMTP 服务器与邮件模板
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    // 使用 STARTTLS 升级连接，本地 SMTP 测试服务可关闭
+    pub smtp_starttls: bool,
+    pub smtp_username: Option<String>,
+    #[serde(skip_serializing)]
+    pub smtp_password: Option<String>,
+    pub from: String,
+    pub subject_template: Option<String>,
+    pub body_template: Option<String>,
+}
+
+// 配置会写入启动日志，不能输出密码
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("smtp_starttls", &self.smtp_starttls)
+            .field("smtp_username", &self.smtp_username)
+            .field("smtp_password", &self.smtp_password.as_ref().map(|_| "***"))
+            .field("from", &self.from)
+            .field("subject_template", &self.subject_template)
+            .field("body_template", &self.body_template)
+            .finish()
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+// mapping 是参数名到请求体 JSON 路径的对象
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    pub plugin_id: String,
+    pub token: String,
+    pub mapping: String,
+    pub wait_for_result: bool,
+    pub enabled: bool,
+    pub last_triggered_at: Option<i64>,
+    pub last_execution_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: String,
+    pub failure_policy: FailurePolicy,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// params 中的字符串可以用 {{ steps.<id>.<field> }} 引用上游步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    pub id: String,
+    pub plugin_id: String,
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub run_if: RunCondition,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunCondition {
+    #[default]
+    Success,
+    Failure,
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    #[default]
+    Stop = 0,
+    // 只按各步骤自己的 run_if 跳过，互不依赖的分支继续执行
+    SkipDownstream = 1,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+pub enum WorkflowRunStatus {
+    Running = 0,
+    Completed = 1,
+    Failed = 2,
+    Stopped = 3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub workflow_id: String,
+    pub status: WorkflowRunStatus,
+    pub error_message: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[repr(i32)]
+pub enum StepRunStatus {
+    Pending = 0,
+    Running = 1,
+    Completed = 2,
+    Failed = 3,
+    Skipped = 4,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkflowStepRun {
+    pub step_id: String,
+    pub status: StepRunStatus,
+    pub execution_id: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
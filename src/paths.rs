@@ -7,6 +7,8 @@ const WORK_DIR: &str = "work_dir";
 const CONF_DIR: &str = "conf";
 const DATA_DIR: &str = "data";
 const PYTHON_ENVS_DIR: &str = "python_envs";
+const ARTIFACTS_DIR: &str = "artifacts";
+const LOGS_DIR: &str = "logs";
 const HOME_ENV: &str = "ANTHILL_HOME";
 
 pub fn install_root() -> Result<PathBuf> {
@@ -53,3 +55,11 @@ pub fn data_dir() -> Result<PathBuf> {
 pub fn python_envs_dir() -> Result<PathBuf> {
     Ok(data_dir()?.join(PYTHON_ENVS_DIR))
 }
+
+pub fn artifacts_dir() -> Result<PathBuf> {
+    Ok(data_dir()?.join(ARTIFACTS_DIR))
+}
+
+pub fn logs_dir() -> Result<PathBuf> {
+    Ok(data_dir()?.join(LOGS_DIR))
+}
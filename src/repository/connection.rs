@@ -25,6 +25,9 @@ pub async fn establish_connection(database_url: &str) -> Result<DbPool> {
             version TEXT NOT NULL,
             min_anthill_version TEXT,
             plugin_type INTEGER NOT NULL,
+            runtime TEXT,
+            interpreter TEXT,
+            permissions TEXT,
             description TEXT,
             author TEXT,
             plugin_path TEXT NOT NULL,
@@ -36,7 +39,11 @@ pub async fn establish_connection(database_url: &str) -> Result<DbPool> {
             parameter_groups TEXT,
             metadata TEXT,
             python_venv_path TEXT,
-            python_dependencies TEXT
+            python_dependencies TEXT,
+            dependencies TEXT,
+            prepare_timeout_seconds INTEGER,
+            apply_timeout_seconds INTEGER,
+            max_concurrency INTEGER
         );
 
         -- 执行记录表
@@ -50,6 +57,17 @@ pub async fn establish_connection(database_url: &str) -> Result<DbPool> {
             stdout TEXT,
             stderr TEXT,
             preview_payload TEXT,
+            result TEXT,
+            error_message TEXT,
+            progress_percent REAL,
+            progress_step TEXT,
+            progress_message TEXT,
+            input_request TEXT,
+            params TEXT,
+            plugin_version TEXT,
+            entry_point TEXT,
+            rerun_of TEXT,
+            triggered_by TEXT,
             confirm_token TEXT,
             expires_at INTEGER,
             started_at INTEGER NOT NULL,
@@ -57,7 +75,159 @@ pub async fn establish_connection(database_url: &str) -> Result<DbPool> {
             FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
         );
 
+        -- 工作流定义，steps 为 JSON 数组
+        CREATE TABLE IF NOT EXISTS workflows (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            steps TEXT NOT NULL,
+            failure_policy INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS workflow_runs (
+            id TEXT PRIMARY KEY,
+            workflow_id TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            error_message TEXT,
+            started_at INTEGER NOT NULL,
+            finished_at INTEGER,
+            FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+        );
+
+        -- 每次运行中各步骤的状态及其创建的执行记录
+        CREATE TABLE IF NOT EXISTS workflow_run_steps (
+            run_id TEXT NOT NULL,
+            step_id TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            execution_id TEXT,
+            error_message TEXT,
+            started_at INTEGER,
+            finished_at INTEGER,
+            PRIMARY KEY (run_id, step_id),
+            FOREIGN KEY (run_id) REFERENCES workflow_runs(id) ON DELETE CASCADE
+        );
+
+        -- 定时任务，cron 与 interval_seconds 二选一
+        CREATE TABLE IF NOT EXISTS schedules (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            plugin_id TEXT NOT NULL,
+            cron TEXT,
+            interval_seconds INTEGER,
+            timezone TEXT NOT NULL DEFAULT 'UTC',
+            params TEXT NOT NULL DEFAULT '{}',
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            overlap_policy INTEGER NOT NULL DEFAULT 0,
+            last_run_at INTEGER,
+            last_execution_id TEXT,
+            last_error TEXT,
+            next_run_at INTEGER,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
+        );
+
+        -- 入站 webhook，mapping 为参数名到 JSON 路径的对象
+        CREATE TABLE IF NOT EXISTS webhooks (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            plugin_id TEXT NOT NULL,
+            token TEXT NOT NULL UNIQUE,
+            mapping TEXT NOT NULL DEFAULT '{}',
+            wait_for_result BOOLEAN NOT NULL DEFAULT 0,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            last_triggered_at INTEGER,
+            last_execution_id TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
+        );
+
+        -- 目录监视触发器，patterns/events 为 JSON 数组
+        CREATE TABLE IF NOT EXISTS file_watches (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            plugin_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            recursive BOOLEAN NOT NULL DEFAULT 0,
+            patterns TEXT NOT NULL DEFAULT '[]',
+            events TEXT NOT NULL,
+            debounce_ms INTEGER NOT NULL,
+            path_param TEXT NOT NULL,
+            params TEXT NOT NULL DEFAULT '{}',
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            last_triggered_at INTEGER,
+            last_execution_id TEXT,
+            last_error TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
+        );
+
+        -- 执行状态通知的目标地址，plugin_id 为空表示全部插件
+        CREATE TABLE IF NOT EXISTS notification_targets (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            secret TEXT,
+            plugin_id TEXT,
+            events TEXT NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
+        );
+
+        -- 每个目标对每个执行状态只投递一次，失败后按指数退避重试
+        CREATE TABLE IF NOT EXISTS notification_deliveries (
+            id TEXT PRIMARY KEY,
+            target_id TEXT NOT NULL,
+            execution_id TEXT NOT NULL,
+            event INTEGER NOT NULL,
+            payload TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            response_status INTEGER,
+            last_error TEXT,
+            next_attempt_at INTEGER,
+            created_at INTEGER NOT NULL,
+            delivered_at INTEGER,
+            UNIQUE (target_id, execution_id, event),
+            FOREIGN KEY (target_id) REFERENCES notification_targets(id) ON DELETE CASCADE,
+            FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+        );
+
+        -- 执行结束后按结果给订阅地址发送邮件
+        CREATE TABLE IF NOT EXISTS email_subscriptions (
+            id TEXT PRIMARY KEY,
+            email TEXT NOT NULL,
+            plugin_id TEXT,
+            outcome INTEGER NOT NULL DEFAULT 0,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            last_sent_at INTEGER,
+            last_error TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON DELETE CASCADE
+        );
+
+        -- 已为哪些执行给订阅发过邮件，每个执行只发送一次
+        CREATE TABLE IF NOT EXISTS email_sends (
+            subscription_id TEXT NOT NULL,
+            execution_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (subscription_id, execution_id),
+            FOREIGN KEY (subscription_id) REFERENCES email_subscriptions(id) ON DELETE CASCADE,
+            FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+        );
+
         CREATE INDEX IF NOT EXISTS idx_executions_plugin_id ON executions(plugin_id);
+        CREATE INDEX IF NOT EXISTS idx_workflow_runs_workflow_id ON workflow_runs(workflow_id);
+        CREATE INDEX IF NOT EXISTS idx_schedules_next_run_at ON schedules(next_run_at);
+        CREATE INDEX IF NOT EXISTS idx_notification_deliveries_next_attempt_at
+            ON notification_deliveries(status, next_attempt_at);
         CREATE INDEX IF NOT EXISTS idx_plugins_enabled ON plugins(enabled);
         CREATE INDEX IF NOT EXISTS idx_plugins_plugin_id ON plugins(plugin_id);
         CREATE INDEX IF NOT EXISTS idx_plugins_name ON plugins(name);
@@ -70,6 +240,17 @@ pub async fn establish_connection(database_url: &str) -> Result<DbPool> {
     ensure_parameter_groups_column(&pool).await?;
     ensure_metadata_column(&pool).await?;
     ensure_execution_new_columns(&pool).await?;
+    ensure_plugin_timeout_columns(&pool).await?;
+    ensure_max_concurrency_column(&pool).await?;
+    ensure_runtime_column(&pool).await?;
+    ensure_interpreter_column(&pool).await?;
+    ensure_permissions_column(&pool).await?;
+    ensure_dependencies_column(&pool).await?;
+    ensure_execution_output_columns(&pool).await?;
+    ensure_execution_progress_columns(&pool).await?;
+    ensure_input_request_column(&pool).await?;
+    ensure_execution_inputs_columns(&pool).await?;
+    ensure_triggered_by_column(&pool).await?;
 
     Ok(pool)
 }
@@ -134,6 +315,219 @@ async fn ensure_execution_new_columns(pool: &DbPool) -> Result<()> {
     Ok(())
 }
 
+async fn ensure_plugin_timeout_columns(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+
+    let mut has_prepare_timeout = false;
+    let mut has_apply_timeout = false;
+
+    for row in &columns {
+        let name: String = row.get("name");
+        match name.as_str() {
+            "prepare_timeout_seconds" => has_prepare_timeout = true,
+            "apply_timeout_seconds" => has_apply_timeout = true,
+            _ => {}
+        }
+    }
+
+    if !has_prepare_timeout {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN prepare_timeout_seconds INTEGER")
+            .execute(pool)
+            .await?;
+    }
+    if !has_apply_timeout {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN apply_timeout_seconds INTEGER")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn ensure_max_concurrency_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "max_concurrency");
+    if !has_column {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN max_concurrency INTEGER")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_runtime_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "runtime");
+    if !has_column {
+        // 旧插件为空，按插件类型的默认运行时执行
+        sqlx::query("ALTER TABLE plugins ADD COLUMN runtime TEXT")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_interpreter_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "interpreter");
+    if !has_column {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN interpreter TEXT")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_permissions_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "permissions");
+    if !has_column {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN permissions TEXT")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_dependencies_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(plugins)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "dependencies");
+    if !has_column {
+        sqlx::query("ALTER TABLE plugins ADD COLUMN dependencies TEXT")
+            .execute(pool)
+            .await?;
+        // Python 依赖来源与 dependencies 的 JSON 格式相同，直接回填
+        sqlx::query("UPDATE plugins SET dependencies = python_dependencies")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_execution_output_columns(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(executions)")
+        .fetch_all(pool)
+        .await?;
+
+    let mut has_result = false;
+    let mut has_error_message = false;
+
+    for row in &columns {
+        let name: String = row.get("name");
+        match name.as_str() {
+            "result" => has_result = true,
+            "error_message" => has_error_message = true,
+            _ => {}
+        }
+    }
+
+    if !has_result {
+        sqlx::query("ALTER TABLE executions ADD COLUMN result TEXT")
+            .execute(pool)
+            .await?;
+    }
+    if !has_error_message {
+        sqlx::query("ALTER TABLE executions ADD COLUMN error_message TEXT")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn ensure_execution_progress_columns(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(executions)")
+        .fetch_all(pool)
+        .await?;
+    let existing: Vec<String> = columns.iter().map(|row| row.get("name")).collect();
+
+    for (name, column_type) in [
+        ("progress_percent", "REAL"),
+        ("progress_step", "TEXT"),
+        ("progress_message", "TEXT"),
+    ] {
+        if !existing.iter().any(|column| column == name) {
+            sqlx::query(&format!(
+                "ALTER TABLE executions ADD COLUMN {} {}",
+                name, column_type
+            ))
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn ensure_input_request_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(executions)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "input_request");
+    if !has_column {
+        sqlx::query("ALTER TABLE executions ADD COLUMN input_request TEXT")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn ensure_execution_inputs_columns(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(executions)")
+        .fetch_all(pool)
+        .await?;
+    let existing: Vec<String> = columns.iter().map(|row| row.get("name")).collect();
+
+    for name in ["params", "plugin_version", "entry_point", "rerun_of"] {
+        if !existing.iter().any(|column| column == name) {
+            sqlx::query(&format!("ALTER TABLE executions ADD COLUMN {} TEXT", name))
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn ensure_triggered_by_column(pool: &DbPool) -> Result<()> {
+    let columns = sqlx::query("PRAGMA table_info(executions)")
+        .fetch_all(pool)
+        .await?;
+    let has_column = columns
+        .iter()
+        .any(|row| row.get::<String, _>("name") == "triggered_by");
+    if !has_column {
+        sqlx::query("ALTER TABLE executions ADD COLUMN triggered_by TEXT")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
 async fn ensure_parameter_groups_column(pool: &DbPool) -> Result<()> {
     let columns = sqlx::query("PRAGMA table_info(plugins)")
         .fetch_all(pool)
@@ -0,0 +1,170 @@
+use crate::error::{AppError, Result};
+use crate::models::{EmailSubscription, ExecutionStatus};
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct EmailSubscriptionRepository {
+    pool: DbPool,
+}
+
+impl EmailSubscriptionRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<EmailSubscription>> {
+        let subscriptions = sqlx::query_as::<_, EmailSubscription>(
+            "SELECT * FROM email_subscriptions ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    pub async fn list_for_plugin(&self, plugin_id: &str) -> Result<Vec<EmailSubscription>> {
+        let subscriptions = sqlx::query_as::<_, EmailSubscription>(
+            r#"
+            SELECT * FROM email_subscriptions
+            WHERE enabled = 1 AND (plugin_id IS NULL OR plugin_id = ?)
+            "#,
+        )
+        .bind(plugin_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<EmailSubscription> {
+        let subscription = sqlx::query_as::<_, EmailSubscription>(
+            "SELECT * FROM email_subscriptions WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::EmailSubscriptionNotFound(id.to_string()))?;
+        Ok(subscription)
+    }
+
+    pub async fn create(&self, subscription: &EmailSubscription) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_subscriptions (
+                id, email, plugin_id, outcome, enabled, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&subscription.id)
+        .bind(&subscription.email)
+        .bind(&subscription.plugin_id)
+        .bind(subscription.outcome as i32)
+        .bind(subscription.enabled)
+        .bind(subscription.created_at)
+        .bind(subscription.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update(&self, subscription: &EmailSubscription) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_subscriptions
+            SET email = ?, plugin_id = ?, outcome = ?, enabled = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&subscription.email)
+        .bind(&subscription.plugin_id)
+        .bind(subscription.outcome as i32)
+        .bind(subscription.enabled)
+        .bind(subscription.updated_at)
+        .bind(&subscription.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::EmailSubscriptionNotFound(subscription.id.clone()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM email_subscriptions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::EmailSubscriptionNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    // 已经发送过时返回 false
+    pub async fn claim_send(&self, subscription_id: &str, execution_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO email_sends (subscription_id, execution_id, created_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(execution_id)
+        .bind(Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_missed(
+        &self,
+        subscription: &EmailSubscription,
+        statuses: &[ExecutionStatus],
+        since: i64,
+    ) -> Result<Vec<(String, ExecutionStatus)>> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; statuses.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT e.id, e.status FROM executions e
+            WHERE (? IS NULL OR e.plugin_id = ?)
+              AND e.finished_at >= ?
+              AND e.status IN ({})
+              AND NOT EXISTS (
+                  SELECT 1 FROM email_sends s
+                  WHERE s.subscription_id = ? AND s.execution_id = e.id
+              )
+            ORDER BY e.finished_at
+            "#,
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, (String, ExecutionStatus)>(&sql)
+            .bind(&subscription.plugin_id)
+            .bind(&subscription.plugin_id)
+            .bind(since);
+        for status in statuses {
+            query = query.bind(*status as i32);
+        }
+        let missed = query.bind(&subscription.id).fetch_all(&self.pool).await?;
+        Ok(missed)
+    }
+
+    pub async fn record_send(&self, id: &str, error: Option<String>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_subscriptions
+            SET last_sent_at = CASE WHEN ? IS NULL THEN ? ELSE last_sent_at END, last_error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&error)
+        .bind(Utc::now().timestamp_millis())
+        .bind(&error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
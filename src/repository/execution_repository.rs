@@ -1,5 +1,7 @@
 use crate::error::{AppError, Result};
-use crate::models::{Execution, ExecutionPhase, ExecutionStatus};
+use crate::models::{
+    Execution, ExecutionOrigin, ExecutionPhase, ExecutionProgress, ExecutionStatus, Plugin,
+};
 use crate::repository::DbPool;
 use chrono::Utc;
 
@@ -15,15 +17,17 @@ impl ExecutionRepository {
 
     pub async fn create_with_phase(
         &self,
-        plugin_id: &str,
+        plugin: &Plugin,
         phase: ExecutionPhase,
+        params: Option<String>,
+        origin: ExecutionOrigin,
     ) -> Result<Execution> {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now().timestamp_millis();
 
         let execution = Execution {
             id: id.clone(),
-            plugin_id: plugin_id.to_string(),
+            plugin_id: plugin.plugin_id.clone(),
             phase,
             status: ExecutionStatus::Pending,
             pid: None,
@@ -31,16 +35,26 @@ impl ExecutionRepository {
             stdout: None,
             stderr: None,
             preview_payload: None,
+            result: None,
+            error_message: None,
+            progress: ExecutionProgress::default(),
+            input_request: None,
+            params,
+            plugin_version: Some(plugin.version.clone()),
+            entry_point: Some(plugin.entry_point.clone()),
+            rerun_of: origin.rerun_of,
+            triggered_by: origin.triggered_by,
             confirm_token: None,
             expires_at: None,
             started_at: now,
             finished_at: None,
+            queue_position: None,
         };
 
         sqlx::query(
             r#"
-            INSERT INTO executions (id, plugin_id, phase, status, started_at, finished_at)
-            VALUES (?, ?, ?, ?, ?, NULL)
+            INSERT INTO executions (id, plugin_id, phase, status, started_at, finished_at, params, plugin_version, entry_point, rerun_of, triggered_by)
+            VALUES (?, ?, ?, ?, ?, NULL, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&execution.id)
@@ -48,6 +62,11 @@ impl ExecutionRepository {
         .bind(execution.phase as i32)
         .bind(execution.status as i32)
         .bind(execution.started_at)
+        .bind(&execution.params)
+        .bind(&execution.plugin_version)
+        .bind(&execution.entry_point)
+        .bind(&execution.rerun_of)
+        .bind(&execution.triggered_by)
         .execute(&self.pool)
         .await?;
 
@@ -84,15 +103,36 @@ impl ExecutionRepository {
         Ok(executions)
     }
 
-    pub async fn update_pid(&self, id: &str, pid: u32) -> Result<()> {
-        sqlx::query("UPDATE executions SET pid = ?, status = ? WHERE id = ?")
-            .bind(pid as i32)
-            .bind(ExecutionStatus::Running as i32)
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+    pub async fn list_by_statuses(&self, statuses: &[ExecutionStatus]) -> Result<Vec<Execution>> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; statuses.len()].join(", ");
+        let sql = format!(
+            "SELECT * FROM executions WHERE status IN ({}) ORDER BY started_at ASC",
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, Execution>(&sql);
+        for status in statuses {
+            query = query.bind(*status as i32);
+        }
+        let executions = query.fetch_all(&self.pool).await?;
 
-        Ok(())
+        Ok(executions)
+    }
+
+    // 执行期间被停止时返回 false
+    pub async fn update_pid(&self, id: &str, pid: u32) -> Result<bool> {
+        let result =
+            sqlx::query("UPDATE executions SET pid = ?, status = ? WHERE id = ? AND status = ?")
+                .bind(pid as i32)
+                .bind(ExecutionStatus::Running as i32)
+                .bind(id)
+                .bind(ExecutionStatus::Pending as i32)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn update_result(
@@ -134,16 +174,15 @@ impl ExecutionRepository {
         sqlx::query(
             r#"
             UPDATE executions
-            SET stdout = ?, stderr = ?, exit_code = ?, status = ?, finished_at = ?, preview_payload = ?, confirm_token = ?, expires_at = ?
+            SET stdout = ?, stderr = ?, exit_code = ?, status = ?, finished_at = ?, confirm_token = ?, expires_at = ?
             WHERE id = ?
             "#,
         )
-        .bind(stdout.clone())
+        .bind(stdout)
         .bind(stderr)
         .bind(exit_code)
         .bind(ExecutionStatus::PreviewReady as i32)
         .bind(Utc::now().timestamp_millis())
-        .bind(stdout)
         .bind(confirm_token)
         .bind(expires_at)
         .bind(id)
@@ -152,16 +191,128 @@ impl ExecutionRepository {
         Ok(())
     }
 
-    pub async fn begin_apply(&self, id: &str) -> Result<()> {
+    // 没有新预览时保留原计划，apply 阶段仍会引用它
+    pub async fn update_output(
+        &self,
+        id: &str,
+        preview_payload: Option<String>,
+        result: Option<String>,
+        error_message: Option<String>,
+    ) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE executions
-            SET phase = ?, status = ?, pid = NULL, exit_code = NULL, stdout = NULL, stderr = NULL, started_at = ?, finished_at = NULL, confirm_token = NULL
+            SET preview_payload = COALESCE(?, preview_payload), result = ?, error_message = ?, input_request = NULL
             WHERE id = ?
             "#,
         )
+        .bind(preview_payload)
+        .bind(result)
+        .bind(error_message)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn request_input(&self, id: &str, input_request: &str) -> Result<()> {
+        sqlx::query("UPDATE executions SET status = ?, input_request = ? WHERE id = ?")
+            .bind(ExecutionStatus::AwaitingInput as i32)
+            .bind(input_request)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn answer_input(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE executions SET status = ?, input_request = NULL WHERE id = ? AND status = ?",
+        )
+        .bind(ExecutionStatus::Running as i32)
+        .bind(id)
+        .bind(ExecutionStatus::AwaitingInput as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_progress(&self, id: &str, progress: &ExecutionProgress) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE executions
+            SET progress_percent = ?, progress_step = ?, progress_message = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(progress.percent)
+        .bind(&progress.step)
+        .bind(&progress.message)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn begin_apply(
+        &self,
+        id: &str,
+        plugin: &Plugin,
+        params: Option<String>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE executions
+            SET phase = ?, status = ?, params = ?, plugin_version = ?, entry_point = ?, pid = NULL, exit_code = NULL, stdout = NULL, stderr = NULL, result = NULL, error_message = NULL, progress_percent = NULL, progress_step = NULL, progress_message = NULL, started_at = ?, finished_at = NULL, confirm_token = NULL
+            WHERE id = ? AND status = ?
+            "#,
+        )
         .bind(ExecutionPhase::Apply as i32)
         .bind(ExecutionStatus::Pending as i32)
+        .bind(params)
+        .bind(&plugin.version)
+        .bind(&plugin.entry_point)
+        .bind(Utc::now().timestamp_millis())
+        .bind(id)
+        .bind(ExecutionStatus::PreviewReady as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_expired_previews(&self, now: i64) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            "SELECT id FROM executions WHERE status = ? AND expires_at < ? ORDER BY started_at ASC",
+        )
+        .bind(ExecutionStatus::PreviewReady as i32)
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    pub async fn close_preview(&self, id: &str, status: ExecutionStatus) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE executions SET status = ?, confirm_token = NULL WHERE id = ? AND status = ?",
+        )
+        .bind(status as i32)
+        .bind(id)
+        .bind(ExecutionStatus::PreviewReady as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_interrupted(&self, id: &str, reason: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE executions
+            SET status = ?, stderr = COALESCE(stderr || char(10), '') || ?, finished_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(ExecutionStatus::Failed as i32)
+        .bind(reason)
         .bind(Utc::now().timestamp_millis())
         .bind(id)
         .execute(&self.pool)
@@ -169,13 +320,132 @@ impl ExecutionRepository {
         Ok(())
     }
 
-    pub async fn update_status(&self, id: &str, status: ExecutionStatus) -> Result<()> {
-        sqlx::query("UPDATE executions SET status = ? WHERE id = ?")
-            .bind(status as i32)
-            .bind(id)
-            .execute(&self.pool)
+    pub async fn list_finished_before(
+        &self,
+        before: i64,
+        plugin_id: Option<&str>,
+        statuses: Option<&[ExecutionStatus]>,
+    ) -> Result<Vec<String>> {
+        let mut sql = format!(
+            "SELECT id FROM executions WHERE {} AND COALESCE(finished_at, started_at) < ?",
+            Self::finished_condition()
+        );
+        if plugin_id.is_some() {
+            sql.push_str(" AND plugin_id = ?");
+        }
+        if let Some(statuses) = statuses {
+            sql.push_str(&format!(" AND status IN ({})", Self::status_list(statuses)));
+        }
+        sql.push_str(" ORDER BY started_at ASC");
+
+        let mut query = sqlx::query_scalar::<_, String>(&sql)
+            .bind(Utc::now().timestamp_millis())
+            .bind(before);
+        if let Some(plugin_id) = plugin_id {
+            query = query.bind(plugin_id);
+        }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    pub async fn list_finished_beyond(
+        &self,
+        keep: usize,
+        exclude: &[ExecutionStatus],
+    ) -> Result<Vec<String>> {
+        let mut sql = format!(
+            "SELECT id FROM executions WHERE {}",
+            Self::finished_condition()
+        );
+        if !exclude.is_empty() {
+            sql.push_str(&format!(
+                " AND status NOT IN ({})",
+                Self::status_list(exclude)
+            ));
+        }
+        let sql = format!(
+            r#"
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (PARTITION BY plugin_id ORDER BY started_at DESC) AS position
+                FROM executions
+                WHERE id IN ({})
+            )
+            WHERE position > ?
+            "#,
+            sql
+        );
+
+        let ids = sqlx::query_scalar::<_, String>(&sql)
+            .bind(Utc::now().timestamp_millis())
+            .bind(keep as i64)
+            .fetch_all(&self.pool)
             .await?;
+        Ok(ids)
+    }
+
+    // 工作流运行引用的执行不会删除，返回 false
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM executions WHERE id = ? \
+             AND id NOT IN (SELECT execution_id FROM workflow_run_steps WHERE execution_id IS NOT NULL)",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
 
+    pub async fn vacuum(&self) -> Result<()> {
+        sqlx::query("VACUUM").execute(&self.pool).await?;
         Ok(())
     }
+
+    // 不会再被进程修改的记录：已结束的执行和已过期的预览，唯一的参数是当前时间
+    fn finished_condition() -> String {
+        // 工作流运行引用的执行记录随工作流一起删除，不参与批量清理
+        format!(
+            "(status IN ({}) OR (status = {} AND expires_at < ?)) \
+             AND id NOT IN (SELECT execution_id FROM workflow_run_steps WHERE execution_id IS NOT NULL)",
+            Self::status_list(&[
+                ExecutionStatus::Completed,
+                ExecutionStatus::Failed,
+                ExecutionStatus::Stopped,
+                ExecutionStatus::TimedOut,
+                ExecutionStatus::Expired,
+            ]),
+            ExecutionStatus::PreviewReady as i32
+        )
+    }
+
+    fn status_list(statuses: &[ExecutionStatus]) -> String {
+        statuses
+            .iter()
+            .map(|status| (*status as i32).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub async fn finish_if(
+        &self,
+        id: &str,
+        status: ExecutionStatus,
+        expected: &[ExecutionStatus],
+    ) -> Result<bool> {
+        if expected.is_empty() {
+            return Ok(false);
+        }
+        let placeholders = vec!["?"; expected.len()].join(", ");
+        let sql = format!(
+            "UPDATE executions SET status = ?, finished_at = ? WHERE id = ? AND status IN ({})",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(status as i32)
+            .bind(Utc::now().timestamp_millis())
+            .bind(id);
+        for expected in expected {
+            query = query.bind(*expected as i32);
+        }
+        let result = query.execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
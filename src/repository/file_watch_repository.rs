@@ -0,0 +1,133 @@
+use crate::error::{AppError, Result};
+use crate::models::FileWatch;
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct FileWatchRepository {
+    pool: DbPool,
+}
+
+impl FileWatchRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<FileWatch>> {
+        let watches =
+            sqlx::query_as::<_, FileWatch>("SELECT * FROM file_watches ORDER BY created_at DESC")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(watches)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<FileWatch> {
+        let watch = sqlx::query_as::<_, FileWatch>("SELECT * FROM file_watches WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::WatchNotFound(id.to_string()))?;
+        Ok(watch)
+    }
+
+    pub async fn create(&self, watch: &FileWatch) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO file_watches (
+                id, name, plugin_id, path, recursive, patterns, events, debounce_ms,
+                path_param, params, enabled, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&watch.id)
+        .bind(&watch.name)
+        .bind(&watch.plugin_id)
+        .bind(&watch.path)
+        .bind(watch.recursive)
+        .bind(&watch.patterns)
+        .bind(&watch.events)
+        .bind(watch.debounce_ms)
+        .bind(&watch.path_param)
+        .bind(&watch.params)
+        .bind(watch.enabled)
+        .bind(watch.created_at)
+        .bind(watch.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update(&self, watch: &FileWatch) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE file_watches
+            SET name = ?, plugin_id = ?, path = ?, recursive = ?, patterns = ?, events = ?,
+                debounce_ms = ?, path_param = ?, params = ?, enabled = ?, last_error = NULL,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&watch.name)
+        .bind(&watch.plugin_id)
+        .bind(&watch.path)
+        .bind(watch.recursive)
+        .bind(&watch.patterns)
+        .bind(&watch.events)
+        .bind(watch.debounce_ms)
+        .bind(&watch.path_param)
+        .bind(&watch.params)
+        .bind(watch.enabled)
+        .bind(watch.updated_at)
+        .bind(&watch.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WatchNotFound(watch.id.clone()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM file_watches WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WatchNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn record_trigger(
+        &self,
+        id: &str,
+        execution_id: Option<&str>,
+        error: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE file_watches
+            SET last_triggered_at = ?, last_execution_id = COALESCE(?, last_execution_id),
+                last_error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(Utc::now().timestamp_millis())
+        .bind(execution_id)
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_error(&self, id: &str, error: &str) -> Result<()> {
+        sqlx::query("UPDATE file_watches SET last_error = ? WHERE id = ?")
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
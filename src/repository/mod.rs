@@ -1,9 +1,21 @@
 pub mod connection;
+pub mod email_subscription_repository;
 pub mod execution_repository;
+pub mod file_watch_repository;
+pub mod notification_repository;
 pub mod plugin_repository;
+pub mod schedule_repository;
+pub mod webhook_repository;
+pub mod workflow_repository;
 
 pub use connection::establish_connection;
+pub use email_subscription_repository::EmailSubscriptionRepository;
 pub use execution_repository::ExecutionRepository;
+pub use file_watch_repository::FileWatchRepository;
+pub use notification_repository::NotificationRepository;
 pub use plugin_repository::PluginRepository;
+pub use schedule_repository::ScheduleRepository;
+pub use webhook_repository::WebhookRepository;
+pub use workflow_repository::WorkflowRepository;
 
 pub type DbPool = sqlx::SqlitePool;
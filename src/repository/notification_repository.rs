@@ -0,0 +1,310 @@
+use crate::error::{AppError, Result};
+use crate::models::{DeliveryStatus, ExecutionStatus, NotificationDelivery, NotificationTarget};
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Debug, Default)]
+pub struct DeliveryFilter {
+    pub target_id: Option<String>,
+    pub execution_id: Option<String>,
+    pub status: Option<DeliveryStatus>,
+    pub limit: i64,
+}
+
+#[derive(Clone)]
+pub struct NotificationRepository {
+    pool: DbPool,
+}
+
+impl NotificationRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_targets(&self) -> Result<Vec<NotificationTarget>> {
+        let targets = sqlx::query_as::<_, NotificationTarget>(
+            "SELECT * FROM notification_targets ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(targets)
+    }
+
+    pub async fn list_targets_for_plugin(
+        &self,
+        plugin_id: &str,
+    ) -> Result<Vec<NotificationTarget>> {
+        let targets = sqlx::query_as::<_, NotificationTarget>(
+            r#"
+            SELECT * FROM notification_targets
+            WHERE enabled = 1 AND (plugin_id IS NULL OR plugin_id = ?)
+            "#,
+        )
+        .bind(plugin_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(targets)
+    }
+
+    pub async fn get_target(&self, id: &str) -> Result<NotificationTarget> {
+        let target = sqlx::query_as::<_, NotificationTarget>(
+            "SELECT * FROM notification_targets WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotificationTargetNotFound(id.to_string()))?;
+        Ok(target)
+    }
+
+    pub async fn create_target(&self, target: &NotificationTarget) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_targets (
+                id, name, url, secret, plugin_id, events, enabled, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&target.id)
+        .bind(&target.name)
+        .bind(&target.url)
+        .bind(&target.secret)
+        .bind(&target.plugin_id)
+        .bind(&target.events)
+        .bind(target.enabled)
+        .bind(target.created_at)
+        .bind(target.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_target(&self, target: &NotificationTarget) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notification_targets
+            SET name = ?, url = ?, secret = ?, plugin_id = ?, events = ?, enabled = ?,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&target.name)
+        .bind(&target.url)
+        .bind(&target.secret)
+        .bind(&target.plugin_id)
+        .bind(&target.events)
+        .bind(target.enabled)
+        .bind(target.updated_at)
+        .bind(&target.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotificationTargetNotFound(target.id.clone()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_target(&self, id: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM notification_targets WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotificationTargetNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    // 已经为该执行的这个状态通知过时返回 false
+    pub async fn create_delivery(&self, delivery: &NotificationDelivery) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO notification_deliveries (
+                id, target_id, execution_id, event, payload, status, attempts, next_attempt_at,
+                created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, 0, ?, ?)
+            "#,
+        )
+        .bind(&delivery.id)
+        .bind(&delivery.target_id)
+        .bind(&delivery.execution_id)
+        .bind(delivery.event as i32)
+        .bind(&delivery.payload)
+        .bind(delivery.status as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // since 之后开始或结束、当前状态已订阅但还没有入队的执行
+    pub async fn list_missed(
+        &self,
+        target: &NotificationTarget,
+        events: &[ExecutionStatus],
+        since: i64,
+    ) -> Result<Vec<(String, ExecutionStatus)>> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; events.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT e.id, e.status FROM executions e
+            WHERE (? IS NULL OR e.plugin_id = ?)
+              AND COALESCE(e.finished_at, e.started_at) >= ?
+              AND e.status IN ({})
+              AND NOT EXISTS (
+                  SELECT 1 FROM notification_deliveries d
+                  WHERE d.target_id = ? AND d.execution_id = e.id AND d.event = e.status
+              )
+            ORDER BY e.started_at
+            "#,
+            placeholders
+        );
+        let mut query = sqlx::query_as::<_, (String, ExecutionStatus)>(&sql)
+            .bind(&target.plugin_id)
+            .bind(&target.plugin_id)
+            .bind(since);
+        for event in events {
+            query = query.bind(*event as i32);
+        }
+        let missed = query.bind(&target.id).fetch_all(&self.pool).await?;
+        Ok(missed)
+    }
+
+    pub async fn get_delivery(&self, id: &str) -> Result<NotificationDelivery> {
+        let delivery = sqlx::query_as::<_, NotificationDelivery>(
+            "SELECT * FROM notification_deliveries WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::DeliveryNotFound(id.to_string()))?;
+        Ok(delivery)
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        filter: &DeliveryFilter,
+    ) -> Result<Vec<NotificationDelivery>> {
+        let deliveries = sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            SELECT * FROM notification_deliveries
+            WHERE (? IS NULL OR target_id = ?)
+              AND (? IS NULL OR execution_id = ?)
+              AND (? IS NULL OR status = ?)
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&filter.target_id)
+        .bind(&filter.target_id)
+        .bind(&filter.execution_id)
+        .bind(&filter.execution_id)
+        .bind(filter.status.map(|status| status as i32))
+        .bind(filter.status.map(|status| status as i32))
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    // 把下次尝试推迟到 lease_until，重启中断的投递之后会重试
+    pub async fn claim_due(&self, now: i64, lease_until: i64) -> Result<Vec<NotificationDelivery>> {
+        let deliveries = sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            UPDATE notification_deliveries
+            SET next_attempt_at = ?
+            WHERE status = ? AND next_attempt_at <= ?
+            RETURNING *
+            "#,
+        )
+        .bind(lease_until)
+        .bind(DeliveryStatus::Pending as i32)
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    pub async fn next_attempt_at(&self) -> Result<Option<i64>> {
+        let next: Option<i64> = sqlx::query_scalar(
+            "SELECT MIN(next_attempt_at) FROM notification_deliveries WHERE status = ?",
+        )
+        .bind(DeliveryStatus::Pending as i32)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(next)
+    }
+
+    pub async fn mark_delivered(&self, id: &str, response_status: i64) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        sqlx::query(
+            r#"
+            UPDATE notification_deliveries
+            SET status = ?, attempts = attempts + 1, response_status = ?, last_error = NULL,
+                next_attempt_at = NULL, delivered_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(DeliveryStatus::Succeeded as i32)
+        .bind(response_status)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // 没有 next_attempt_at 时放弃投递
+    pub async fn mark_attempt_failed(
+        &self,
+        id: &str,
+        response_status: Option<i64>,
+        error: &str,
+        next_attempt_at: Option<i64>,
+    ) -> Result<()> {
+        let status = if next_attempt_at.is_some() {
+            DeliveryStatus::Pending
+        } else {
+            DeliveryStatus::Failed
+        };
+        sqlx::query(
+            r#"
+            UPDATE notification_deliveries
+            SET status = ?, attempts = attempts + 1, response_status = ?, last_error = ?,
+                next_attempt_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(status as i32)
+        .bind(response_status)
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn retry_delivery(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notification_deliveries
+            SET status = ?, attempts = 0, next_attempt_at = ?
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(DeliveryStatus::Pending as i32)
+        .bind(Utc::now().timestamp_millis())
+        .bind(id)
+        .bind(DeliveryStatus::Failed as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
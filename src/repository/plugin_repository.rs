@@ -16,9 +16,9 @@ impl PluginRepository {
     pub async fn list(&self) -> Result<Vec<Plugin>> {
         let plugins = sqlx::query_as::<_, Plugin>(
             r#"
-            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, description, author, plugin_path, entry_point,
+            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, runtime, interpreter, permissions, description, author, plugin_path, entry_point,
                    enabled, created_at, updated_at, parameters, parameter_groups, metadata,
-                   python_venv_path, python_dependencies
+                   python_venv_path, python_dependencies, dependencies, prepare_timeout_seconds, apply_timeout_seconds, max_concurrency
             FROM plugins
             ORDER BY created_at DESC
             "#,
@@ -32,9 +32,9 @@ impl PluginRepository {
     pub async fn get(&self, id: &str) -> Result<Plugin> {
         let plugin = sqlx::query_as::<_, Plugin>(
             r#"
-            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, description, author, plugin_path, entry_point,
+            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, runtime, interpreter, permissions, description, author, plugin_path, entry_point,
                    enabled, created_at, updated_at, parameters, parameter_groups, metadata,
-                   python_venv_path, python_dependencies
+                   python_venv_path, python_dependencies, dependencies, prepare_timeout_seconds, apply_timeout_seconds, max_concurrency
             FROM plugins
             WHERE plugin_id = ?
             "#,
@@ -50,9 +50,9 @@ impl PluginRepository {
     pub async fn get_by_name(&self, name: &str) -> Result<Plugin> {
         let plugin = sqlx::query_as::<_, Plugin>(
             r#"
-            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, description, author, plugin_path, entry_point,
+            SELECT id, plugin_id, name, version, min_anthill_version, plugin_type, runtime, interpreter, permissions, description, author, plugin_path, entry_point,
                    enabled, created_at, updated_at, parameters, parameter_groups, metadata,
-                   python_venv_path, python_dependencies
+                   python_venv_path, python_dependencies, dependencies, prepare_timeout_seconds, apply_timeout_seconds, max_concurrency
             FROM plugins
             WHERE name = ?
             "#,
@@ -68,8 +68,8 @@ impl PluginRepository {
     pub async fn create(&self, plugin: &Plugin) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO plugins (id, plugin_id, name, version, min_anthill_version, plugin_type, description, author, plugin_path, entry_point, enabled, created_at, updated_at, parameters, parameter_groups, metadata, python_venv_path, python_dependencies)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO plugins (id, plugin_id, name, version, min_anthill_version, plugin_type, runtime, interpreter, permissions, description, author, plugin_path, entry_point, enabled, created_at, updated_at, parameters, parameter_groups, metadata, python_venv_path, python_dependencies, dependencies, prepare_timeout_seconds, apply_timeout_seconds, max_concurrency)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&plugin.id)
@@ -78,6 +78,9 @@ impl PluginRepository {
         .bind(&plugin.version)
         .bind(&plugin.min_anthill_version)
         .bind(plugin.plugin_type as i32)
+        .bind(&plugin.runtime)
+        .bind(&plugin.interpreter)
+        .bind(&plugin.permissions)
         .bind(&plugin.description)
         .bind(&plugin.author)
         .bind(&plugin.plugin_path)
@@ -90,6 +93,10 @@ impl PluginRepository {
         .bind(&plugin.metadata)
         .bind(&plugin.python_venv_path)
         .bind(&plugin.python_dependencies)
+        .bind(&plugin.dependencies)
+        .bind(plugin.prepare_timeout_seconds)
+        .bind(plugin.apply_timeout_seconds)
+        .bind(plugin.max_concurrency)
         .execute(&self.pool)
         .await?;
 
@@ -101,7 +108,7 @@ impl PluginRepository {
         sqlx::query(
             r#"
             UPDATE plugins
-            SET name = ?, version = ?, min_anthill_version = ?, plugin_type = ?, description = ?, author = ?, plugin_path = ?, entry_point = ?, enabled = ?, updated_at = ?, parameters = ?, parameter_groups = ?, metadata = ?, python_venv_path = ?, python_dependencies = ?
+            SET name = ?, version = ?, min_anthill_version = ?, plugin_type = ?, runtime = ?, interpreter = ?, permissions = ?, description = ?, author = ?, plugin_path = ?, entry_point = ?, enabled = ?, updated_at = ?, parameters = ?, parameter_groups = ?, metadata = ?, python_venv_path = ?, python_dependencies = ?, dependencies = ?, prepare_timeout_seconds = ?, apply_timeout_seconds = ?, max_concurrency = ?
             WHERE plugin_id = ?
             "#,
         )
@@ -109,6 +116,9 @@ impl PluginRepository {
         .bind(&plugin.version)
         .bind(&plugin.min_anthill_version)
         .bind(plugin.plugin_type as i32)
+        .bind(&plugin.runtime)
+        .bind(&plugin.interpreter)
+        .bind(&plugin.permissions)
         .bind(&plugin.description)
         .bind(&plugin.author)
         .bind(&plugin.plugin_path)
@@ -120,6 +130,10 @@ impl PluginRepository {
         .bind(&plugin.metadata)
         .bind(&plugin.python_venv_path)
         .bind(&plugin.python_dependencies)
+        .bind(&plugin.dependencies)
+        .bind(plugin.prepare_timeout_seconds)
+        .bind(plugin.apply_timeout_seconds)
+        .bind(plugin.max_concurrency)
         .bind(&plugin.plugin_id)
         .execute(&self.pool)
         .await?;
@@ -0,0 +1,165 @@
+use crate::error::{AppError, Result};
+use crate::models::Schedule;
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct ScheduleRepository {
+    pool: DbPool,
+}
+
+impl ScheduleRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Schedule>> {
+        let schedules =
+            sqlx::query_as::<_, Schedule>("SELECT * FROM schedules ORDER BY created_at DESC")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(schedules)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Schedule> {
+        let schedule = sqlx::query_as::<_, Schedule>("SELECT * FROM schedules WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::ScheduleNotFound(id.to_string()))?;
+        Ok(schedule)
+    }
+
+    pub async fn create(&self, schedule: &Schedule) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO schedules (
+                id, name, plugin_id, cron, interval_seconds, timezone, params, enabled,
+                overlap_policy, next_run_at, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&schedule.id)
+        .bind(&schedule.name)
+        .bind(&schedule.plugin_id)
+        .bind(&schedule.cron)
+        .bind(schedule.interval_seconds)
+        .bind(&schedule.timezone)
+        .bind(&schedule.params)
+        .bind(schedule.enabled)
+        .bind(schedule.overlap_policy as i32)
+        .bind(schedule.next_run_at)
+        .bind(schedule.created_at)
+        .bind(schedule.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // 运行记录保持不变
+    pub async fn update(&self, schedule: &Schedule) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE schedules
+            SET name = ?, plugin_id = ?, cron = ?, interval_seconds = ?, timezone = ?,
+                params = ?, enabled = ?, overlap_policy = ?, next_run_at = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&schedule.name)
+        .bind(&schedule.plugin_id)
+        .bind(&schedule.cron)
+        .bind(schedule.interval_seconds)
+        .bind(&schedule.timezone)
+        .bind(&schedule.params)
+        .bind(schedule.enabled)
+        .bind(schedule.overlap_policy as i32)
+        .bind(schedule.next_run_at)
+        .bind(schedule.updated_at)
+        .bind(&schedule.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::ScheduleNotFound(schedule.id.clone()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM schedules WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::ScheduleNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_due(&self, now: i64) -> Result<Vec<Schedule>> {
+        let schedules = sqlx::query_as::<_, Schedule>(
+            r#"
+            SELECT * FROM schedules
+            WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?
+            ORDER BY next_run_at ASC
+            "#,
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(schedules)
+    }
+
+    pub async fn next_run_at(&self) -> Result<Option<i64>> {
+        let next: Option<i64> =
+            sqlx::query_scalar("SELECT MIN(next_run_at) FROM schedules WHERE enabled = 1")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(next)
+    }
+
+    // 读取之后计划被修改过时不生效
+    pub async fn advance(&self, id: &str, due_at: i64, next_run_at: Option<i64>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE schedules SET next_run_at = ? WHERE id = ? AND next_run_at = ? AND enabled = 1",
+        )
+        .bind(next_run_at)
+        .bind(id)
+        .bind(due_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn record_run(
+        &self,
+        id: &str,
+        execution_id: Option<&str>,
+        error: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE schedules
+            SET last_run_at = ?, last_execution_id = COALESCE(?, last_execution_id), last_error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(Utc::now().timestamp_millis())
+        .bind(execution_id)
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_skip(&self, id: &str, reason: &str) -> Result<()> {
+        sqlx::query("UPDATE schedules SET last_error = ? WHERE id = ?")
+            .bind(reason)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::{AppError, Result};
+use crate::models::Webhook;
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: DbPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Webhook>> {
+        let webhooks =
+            sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks ORDER BY created_at DESC")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(webhooks)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::WebhookNotFound(id.to_string()))?;
+        Ok(webhook)
+    }
+
+    pub async fn get_by_token(&self, token: &str) -> Result<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE token = ?")
+            .bind(token)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::WebhookNotFound(token.to_string()))?;
+        Ok(webhook)
+    }
+
+    pub async fn create(&self, webhook: &Webhook) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhooks (
+                id, name, plugin_id, token, mapping, wait_for_result, enabled, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.name)
+        .bind(&webhook.plugin_id)
+        .bind(&webhook.token)
+        .bind(&webhook.mapping)
+        .bind(webhook.wait_for_result)
+        .bind(webhook.enabled)
+        .bind(webhook.created_at)
+        .bind(webhook.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update(&self, webhook: &Webhook) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET name = ?, plugin_id = ?, token = ?, mapping = ?, wait_for_result = ?,
+                enabled = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&webhook.name)
+        .bind(&webhook.plugin_id)
+        .bind(&webhook.token)
+        .bind(&webhook.mapping)
+        .bind(webhook.wait_for_result)
+        .bind(webhook.enabled)
+        .bind(webhook.updated_at)
+        .bind(&webhook.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WebhookNotFound(webhook.id.clone()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WebhookNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn record_trigger(&self, id: &str, execution_id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE webhooks SET last_triggered_at = ?, last_execution_id = ? WHERE id = ?",
+        )
+        .bind(Utc::now().timestamp_millis())
+        .bind(execution_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::error::{AppError, Result};
+use crate::models::{StepRunStatus, Workflow, WorkflowRun, WorkflowRunStatus, WorkflowStepRun};
+use crate::repository::DbPool;
+use chrono::Utc;
+
+#[derive(Clone)]
+pub struct WorkflowRepository {
+    pool: DbPool,
+}
+
+impl WorkflowRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<Workflow>> {
+        let workflows =
+            sqlx::query_as::<_, Workflow>("SELECT * FROM workflows ORDER BY created_at DESC")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(workflows)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Workflow> {
+        let workflow = sqlx::query_as::<_, Workflow>("SELECT * FROM workflows WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::WorkflowNotFound(id.to_string()))?;
+        Ok(workflow)
+    }
+
+    pub async fn create(&self, workflow: &Workflow) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO workflows (id, name, description, steps, failure_policy, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&workflow.id)
+        .bind(&workflow.name)
+        .bind(&workflow.description)
+        .bind(&workflow.steps)
+        .bind(workflow.failure_policy as i32)
+        .bind(workflow.created_at)
+        .bind(workflow.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update(&self, workflow: &Workflow) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE workflows
+            SET name = ?, description = ?, steps = ?, failure_policy = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&workflow.name)
+        .bind(&workflow.description)
+        .bind(&workflow.steps)
+        .bind(workflow.failure_policy as i32)
+        .bind(workflow.updated_at)
+        .bind(&workflow.id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WorkflowNotFound(workflow.id.clone()));
+        }
+        Ok(())
+    }
+
+    // 运行创建的执行会保留
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM workflow_run_steps WHERE run_id IN (SELECT id FROM workflow_runs WHERE workflow_id = ?)",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM workflow_runs WHERE workflow_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM workflows WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WorkflowNotFound(id.to_string()));
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn create_run(&self, run: &WorkflowRun, step_ids: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO workflow_runs (id, workflow_id, status, error_message, started_at, finished_at)
+            VALUES (?, ?, ?, NULL, ?, NULL)
+            "#,
+        )
+        .bind(&run.id)
+        .bind(&run.workflow_id)
+        .bind(run.status as i32)
+        .bind(run.started_at)
+        .execute(&mut *tx)
+        .await?;
+        for step_id in step_ids {
+            sqlx::query(
+                "INSERT INTO workflow_run_steps (run_id, step_id, status) VALUES (?, ?, ?)",
+            )
+            .bind(&run.id)
+            .bind(step_id)
+            .bind(StepRunStatus::Pending as i32)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_run(&self, id: &str) -> Result<WorkflowRun> {
+        let run = sqlx::query_as::<_, WorkflowRun>("SELECT * FROM workflow_runs WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::WorkflowRunNotFound(id.to_string()))?;
+        Ok(run)
+    }
+
+    pub async fn list_runs(&self, workflow_id: &str) -> Result<Vec<WorkflowRun>> {
+        let runs = sqlx::query_as::<_, WorkflowRun>(
+            "SELECT * FROM workflow_runs WHERE workflow_id = ? ORDER BY started_at DESC",
+        )
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
+    pub async fn list_runs_by_status(&self, status: WorkflowRunStatus) -> Result<Vec<WorkflowRun>> {
+        let runs = sqlx::query_as::<_, WorkflowRun>(
+            "SELECT * FROM workflow_runs WHERE status = ? ORDER BY started_at ASC",
+        )
+        .bind(status as i32)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
+    // 运行已经结束（例如被停止）时返回 false
+    pub async fn finish_run(
+        &self,
+        id: &str,
+        status: WorkflowRunStatus,
+        error_message: Option<String>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE workflow_runs
+            SET status = ?, error_message = ?, finished_at = ?
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(status as i32)
+        .bind(error_message)
+        .bind(Utc::now().timestamp_millis())
+        .bind(id)
+        .bind(WorkflowRunStatus::Running as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_step_runs(&self, run_id: &str) -> Result<Vec<WorkflowStepRun>> {
+        let steps = sqlx::query_as::<_, WorkflowStepRun>(
+            "SELECT * FROM workflow_run_steps WHERE run_id = ? ORDER BY rowid ASC",
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(steps)
+    }
+
+    pub async fn start_step(&self, run_id: &str, step_id: &str, execution_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE workflow_run_steps
+            SET status = ?, execution_id = ?, started_at = ?
+            WHERE run_id = ? AND step_id = ?
+            "#,
+        )
+        .bind(StepRunStatus::Running as i32)
+        .bind(execution_id)
+        .bind(Utc::now().timestamp_millis())
+        .bind(run_id)
+        .bind(step_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish_step(
+        &self,
+        run_id: &str,
+        step_id: &str,
+        status: StepRunStatus,
+        error_message: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE workflow_run_steps
+            SET status = ?, error_message = ?, finished_at = ?
+            WHERE run_id = ? AND step_id = ?
+            "#,
+        )
+        .bind(status as i32)
+        .bind(error_message)
+        .bind(Utc::now().timestamp_millis())
+        .bind(run_id)
+        .bind(step_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // 提前结束的运行：未开始的步骤标记为跳过，运行中的步骤以 reason 标记失败
+    pub async fn close_steps(&self, run_id: &str, reason: &str) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        sqlx::query(
+            "UPDATE workflow_run_steps SET status = ?, finished_at = ? WHERE run_id = ? AND status = ?",
+        )
+        .bind(StepRunStatus::Skipped as i32)
+        .bind(now)
+        .bind(run_id)
+        .bind(StepRunStatus::Pending as i32)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE workflow_run_steps
+            SET status = ?, error_message = ?, finished_at = ?
+            WHERE run_id = ? AND status = ?
+            "#,
+        )
+        .bind(StepRunStatus::Failed as i32)
+        .bind(reason)
+        .bind(now)
+        .bind(run_id)
+        .bind(StepRunStatus::Running as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::{AppError, Result};
+use crate::models::Artifact;
+use crate::paths;
+use std::path::{Component, Path, PathBuf};
+
+pub const OUTPUT_DIR_NAME: &str = "output";
+
+pub struct ArtifactStore;
+
+impl ArtifactStore {
+    pub fn output_dir(work_dir: &Path) -> PathBuf {
+        work_dir.join(OUTPUT_DIR_NAME)
+    }
+
+    // 同一执行后一阶段的文件会覆盖前一阶段的
+    pub fn collect(execution_id: &str, work_dir: &Path) -> Result<usize> {
+        let output_dir = Self::output_dir(work_dir);
+        if !output_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let target_dir = Self::execution_dir(execution_id)?;
+        let mut collected = 0;
+        for source in Self::walk(&output_dir)? {
+            let Ok(relative) = source.strip_prefix(&output_dir) else {
+                continue;
+            };
+            let target = target_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // rename 跨文件系统会失败，此时退回复制
+            if std::fs::rename(&source, &target).is_err() {
+                std::fs::copy(&source, &target)?;
+            }
+            collected += 1;
+        }
+        Ok(collected)
+    }
+
+    pub fn list(execution_id: &str) -> Result<Vec<Artifact>> {
+        let dir = Self::execution_dir(execution_id)?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut artifacts = Vec::new();
+        for path in Self::walk(&dir)? {
+            let Ok(relative) = path.strip_prefix(&dir) else {
+                continue;
+            };
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            artifacts.push(Self::describe(name, &path)?);
+        }
+        artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(artifacts)
+    }
+
+    // 拒绝跳出执行目录的名称
+    pub fn find(execution_id: &str, name: &str) -> Result<(Artifact, PathBuf)> {
+        let relative = Path::new(name);
+        let is_plain = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if name.is_empty() || !is_plain {
+            return Err(AppError::ArtifactNotFound(name.to_string()));
+        }
+
+        let path = Self::execution_dir(execution_id)?.join(relative);
+        if !path.is_file() {
+            return Err(AppError::ArtifactNotFound(name.to_string()));
+        }
+        Ok((Self::describe(name.to_string(), &path)?, path))
+    }
+
+    pub fn remove(execution_id: &str) -> Result<()> {
+        remove_dir_if_exists(&Self::execution_dir(execution_id)?)
+    }
+
+    fn execution_dir(execution_id: &str) -> Result<PathBuf> {
+        Ok(paths::artifacts_dir()?.join(execution_id))
+    }
+
+    fn describe(name: String, path: &Path) -> Result<Artifact> {
+        let metadata = std::fs::metadata(path)?;
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis());
+        Ok(Artifact {
+            content_type: mime_guess::from_path(path)
+                .first_or_octet_stream()
+                .essence_str()
+                .to_string(),
+            name,
+            size: metadata.len(),
+            modified_at,
+        })
+    }
+
+    // 跳过符号链接，避免插件暴露输出目录以外的文件
+    fn walk(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in std::fs::read_dir(&current)? {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    pending.push(entry.path());
+                } else if file_type.is_file() {
+                    files.push(entry.path());
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+pub(crate) fn remove_dir_if_exists(dir: &Path) -> Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,107 @@
+use crate::models::{Execution, ExecutionStatus, Plugin};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+pub struct QueuedExecution {
+    pub execution: Execution,
+    pub plugin: Plugin,
+    pub success_status: ExecutionStatus,
+    pub env: HashMap<String, String>,
+    pub cleanup_on_success: bool,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: VecDeque<QueuedExecution>,
+    running_total: usize,
+    running_by_plugin: HashMap<String, usize>,
+}
+
+#[derive(Clone)]
+pub struct ExecutionQueue {
+    state: Arc<Mutex<QueueState>>,
+    slot_freed: Arc<Notify>,
+    max_concurrent: Option<usize>,
+}
+
+impl ExecutionQueue {
+    pub fn new(max_concurrent: Option<usize>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(QueueState::default())),
+            slot_freed: Arc::new(Notify::new()),
+            max_concurrent,
+        }
+    }
+
+    pub fn push(&self, job: QueuedExecution) {
+        self.state.lock().unwrap().pending.push_back(job);
+    }
+
+    // 插件达到自身上限时跳过它，不阻塞其他插件
+    pub fn pop_ready(&self) -> Option<QueuedExecution> {
+        let mut state = self.state.lock().unwrap();
+        if self
+            .max_concurrent
+            .is_some_and(|limit| state.running_total >= limit)
+        {
+            return None;
+        }
+
+        let index = state.pending.iter().position(|job| {
+            let running = state
+                .running_by_plugin
+                .get(&job.plugin.plugin_id)
+                .copied()
+                .unwrap_or(0);
+            job.plugin
+                .max_concurrency
+                .is_none_or(|limit| running < limit as usize)
+        })?;
+        let job = state.pending.remove(index)?;
+        state.running_total += 1;
+        *state
+            .running_by_plugin
+            .entry(job.plugin.plugin_id.clone())
+            .or_default() += 1;
+        Some(job)
+    }
+
+    // 归还 pop_ready 占用的名额并唤醒调度
+    pub fn release(&self, plugin_id: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.running_total = state.running_total.saturating_sub(1);
+            if let Some(running) = state.running_by_plugin.get_mut(plugin_id) {
+                *running = running.saturating_sub(1);
+                if *running == 0 {
+                    state.running_by_plugin.remove(plugin_id);
+                }
+            }
+        }
+        self.slot_freed.notify_one();
+    }
+
+    pub fn remove(&self, execution_id: &str) -> Option<QueuedExecution> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .pending
+            .iter()
+            .position(|job| job.execution.id == execution_id)?;
+        state.pending.remove(index)
+    }
+
+    pub fn position(&self, execution_id: &str) -> Option<usize> {
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .iter()
+            .position(|job| job.execution.id == execution_id)
+            .map(|index| index + 1)
+    }
+
+    pub async fn slot_freed(&self) {
+        self.slot_freed.notified().await;
+    }
+}
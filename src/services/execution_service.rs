@@ -1,31 +1,91 @@
 use crate::error::{AppError, Result};
-use crate::executor::{NodeExecutor, PluginExecutor, PythonExecutor};
-use crate::models::{Execution, ExecutionPhase, ExecutionStatus, PluginParamType, PluginParameter};
+use crate::executor::ExecutorRegistry;
+use crate::models::{
+    Artifact, Execution, ExecutionOrigin, ExecutionPhase, ExecutionProgress, ExecutionStatus,
+    LogLimits, LogStream, OrphanPolicy, PluginParamType, PluginParameter,
+};
 use crate::paths;
 use crate::repository::{ExecutionRepository, PluginRepository};
+use crate::services::artifact_store::{ArtifactStore, remove_dir_if_exists};
+use crate::services::execution_queue::{ExecutionQueue, QueuedExecution};
+use crate::services::input_channel::InputChannels;
+use crate::services::log_store::{self, LogChunk, LogWriter, TruncatedCapture};
+use crate::services::log_stream::{LogEvent, LogHub, LogLine, LogSubscription};
+use crate::services::plugin_protocol::{self, InputRequest, PluginEvent, PluginOutput};
+use crate::services::process_registry::{self, ProcessRegistry};
+use crate::services::retention;
+use crate::services::status_watch::{StatusChange, StatusWatch};
 use chrono::Utc;
 use semver::Version;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use tokio::time::{sleep, Duration};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::time::{Duration, Instant, interval, sleep, timeout_at};
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionLimits {
+    pub prepare_timeout_seconds: Option<u64>,
+    pub apply_timeout_seconds: Option<u64>,
+    pub max_concurrent_executions: Option<usize>,
+    pub logs: LogLimits,
+}
+
+const INTERRUPTED_REASON: &str = "Execution interrupted by restart";
+
+enum Termination {
+    Exited,
+    Stopped(process_registry::StopRequest),
+    TimedOut(Duration),
+}
+
+// 读取 stdout 与 stderr 的任务共用的句柄
+#[derive(Clone)]
+struct PipeContext {
+    execution_id: String,
+    exec_repo: ExecutionRepository,
+    logs: LogHub,
+    inputs: InputChannels,
+    statuses: StatusWatch,
+    limits: LogLimits,
+}
 
 #[derive(Clone)]
 pub struct ExecutionService {
     exec_repo: ExecutionRepository,
     plugin_repo: PluginRepository,
-    python_executor: PythonExecutor,
-    node_executor: NodeExecutor,
+    executors: ExecutorRegistry,
+    processes: ProcessRegistry,
+    logs: LogHub,
+    inputs: InputChannels,
+    statuses: StatusWatch,
+    queue: ExecutionQueue,
+    limits: ExecutionLimits,
 }
 
 const PREVIEW_TTL_MS: i64 = 10 * 60 * 1000;
+const PREVIEW_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+// 进度写入数据库的最小间隔，间隔内只保留最新值
+const PROGRESS_WRITE_INTERVAL: Duration = Duration::from_millis(500);
+const REDACTED_VALUE: &str = "******";
 
 impl ExecutionService {
-    pub fn new(exec_repo: ExecutionRepository, plugin_repo: PluginRepository) -> Self {
+    pub fn new(
+        exec_repo: ExecutionRepository,
+        plugin_repo: PluginRepository,
+        executors: ExecutorRegistry,
+        limits: ExecutionLimits,
+    ) -> Self {
         Self {
             exec_repo,
             plugin_repo,
-            python_executor: PythonExecutor::default(),
-            node_executor: NodeExecutor::default(),
+            executors,
+            processes: ProcessRegistry::new(),
+            logs: LogHub::new(),
+            inputs: InputChannels::new(),
+            statuses: StatusWatch::new(),
+            queue: ExecutionQueue::new(limits.max_concurrent_executions),
+            limits,
         }
     }
 
@@ -35,36 +95,86 @@ impl ExecutionService {
         params: HashMap<String, serde_json::Value>,
     ) -> Result<Execution> {
         // 直接执行（无预览）的快捷接口，保持向后兼容
-        let plugin = self.plugin_repo.get(plugin_id).await?;
-        if !plugin.enabled {
-            return Err(AppError::PluginDisabled);
-        }
-        Self::ensure_min_anthill_version(&plugin.min_anthill_version)?;
-
-        let resolved_params = Self::resolve_parameters(&plugin.parameters, params)?;
-        let mut env = HashMap::new();
-        if !resolved_params.is_empty() {
-            let params_json = serde_json::to_string(&resolved_params).map_err(|e| {
-                AppError::Execution(format!("Failed to serialize parameters: {}", e))
-            })?;
-            env.insert("ANTHILL_PLUGIN_PARAMS".to_string(), params_json);
-        }
-        env.insert("ANTHILL_PHASE".to_string(), "apply".to_string());
-
-        self.start_process(
-            plugin,
+        self.start_plugin(
+            plugin_id,
+            params,
             ExecutionPhase::Apply,
-            ExecutionStatus::Completed,
-            env,
-            true,
+            ExecutionOrigin::default(),
         )
         .await
     }
 
+    // 由定时、工作流等触发的执行，来源记录在 triggered_by
+    pub async fn execute_triggered(
+        &self,
+        plugin_id: &str,
+        params: HashMap<String, serde_json::Value>,
+        triggered_by: String,
+    ) -> Result<Execution> {
+        let origin = ExecutionOrigin {
+            rerun_of: None,
+            triggered_by: Some(triggered_by),
+        };
+        self.start_plugin(plugin_id, params, ExecutionPhase::Apply, origin)
+            .await
+    }
+
     pub async fn prepare_plugin(
         &self,
         plugin_id: &str,
         params: HashMap<String, serde_json::Value>,
+    ) -> Result<Execution> {
+        self.start_plugin(
+            plugin_id,
+            params,
+            ExecutionPhase::Prepare,
+            ExecutionOrigin::default(),
+        )
+        .await
+    }
+
+    // 密钥参数不会保存，重跑时需重新提供或使用默认值；经过预览的执行重新走 prepare
+    pub async fn rerun_execution(
+        &self,
+        id: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<Execution> {
+        let original = self.exec_repo.get(id).await?;
+        let plugin = self.plugin_repo.get(&original.plugin_id).await?;
+        let schema = Self::parse_parameters(&plugin.parameters)?;
+
+        let mut merged: HashMap<String, serde_json::Value> = match original.params.as_deref() {
+            Some(raw) => serde_json::from_str(raw)
+                .map_err(|e| AppError::Execution(format!("Invalid stored parameters: {}", e)))?,
+            None => HashMap::new(),
+        };
+        // 插件升级后可能删除了参数，只沿用仍然存在且非机密的参数
+        merged.retain(|name, _| {
+            schema
+                .iter()
+                .any(|param| &param.name == name && !param.is_secret())
+        });
+        merged.extend(params);
+
+        let phase = if original.phase == ExecutionPhase::Prepare || original.expires_at.is_some() {
+            ExecutionPhase::Prepare
+        } else {
+            ExecutionPhase::Apply
+        };
+        let origin = ExecutionOrigin {
+            rerun_of: Some(original.id),
+            triggered_by: None,
+        };
+        self.start_plugin(&original.plugin_id, merged, phase, origin)
+            .await
+    }
+
+    async fn start_plugin(
+        &self,
+        plugin_id: &str,
+        params: HashMap<String, serde_json::Value>,
+        phase: ExecutionPhase,
+        origin: ExecutionOrigin,
     ) -> Result<Execution> {
         let plugin = self.plugin_repo.get(plugin_id).await?;
         if !plugin.enabled {
@@ -73,25 +183,42 @@ impl ExecutionService {
         Self::ensure_min_anthill_version(&plugin.min_anthill_version)?;
 
         let resolved_params = Self::resolve_parameters(&plugin.parameters, params)?;
-        let mut env = HashMap::new();
-        if !resolved_params.is_empty() {
-            let params_json = serde_json::to_string(&resolved_params).map_err(|e| {
-                AppError::Execution(format!("Failed to serialize parameters: {}", e))
-            })?;
-            env.insert("ANTHILL_PLUGIN_PARAMS".to_string(), params_json);
-        }
-        env.insert("ANTHILL_PHASE".to_string(), "prepare".to_string());
+        let stored_params = Self::redact_parameters(&plugin.parameters, &resolved_params)?;
+        let mut env = Self::parameters_env(&resolved_params)?;
+        let (phase_name, success_status, cleanup_on_success) = match phase {
+            ExecutionPhase::Prepare => ("prepare", ExecutionStatus::PreviewReady, false),
+            ExecutionPhase::Apply => ("apply", ExecutionStatus::Completed, true),
+        };
+        env.insert("ANTHILL_PHASE".to_string(), phase_name.to_string());
 
-        self.start_process(
+        let execution = self
+            .exec_repo
+            .create_with_phase(&plugin, phase, stored_params, origin)
+            .await?;
+        self.enqueue(QueuedExecution {
+            execution,
             plugin,
-            ExecutionPhase::Prepare,
-            ExecutionStatus::PreviewReady,
+            success_status,
             env,
-            false,
-        )
+            cleanup_on_success,
+        })
         .await
     }
 
+    pub async fn check_parameters(
+        &self,
+        plugin_id: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let plugin = self.plugin_repo.get(plugin_id).await?;
+        Self::resolve_parameters(&plugin.parameters, params).map(|_| ())
+    }
+
+    pub async fn plugin_parameters(&self, plugin_id: &str) -> Result<Vec<PluginParameter>> {
+        let plugin = self.plugin_repo.get(plugin_id).await?;
+        Self::parse_parameters(&plugin.parameters)
+    }
+
     pub async fn apply_execution(
         &self,
         id: &str,
@@ -104,6 +231,11 @@ impl ExecutionService {
                 "Only preview executions can be applied".to_string(),
             ));
         }
+        if execution.status == ExecutionStatus::Expired {
+            return Err(AppError::Execution(
+                "Preview has expired, please run prepare again".to_string(),
+            ));
+        }
         if execution.status != ExecutionStatus::PreviewReady {
             return Err(AppError::Execution(
                 "Execution is not ready to apply".to_string(),
@@ -112,12 +244,12 @@ impl ExecutionService {
         if execution.confirm_token.as_deref() != Some(confirm_token) {
             return Err(AppError::Execution("Invalid confirm token".to_string()));
         }
-        if let Some(expires_at) = execution.expires_at {
-            if Utc::now().timestamp_millis() > expires_at {
-                return Err(AppError::Execution(
-                    "Preview has expired, please run prepare again".to_string(),
-                ));
-            }
+        if let Some(expires_at) = execution.expires_at
+            && Utc::now().timestamp_millis() > expires_at
+        {
+            return Err(AppError::Execution(
+                "Preview has expired, please run prepare again".to_string(),
+            ));
         }
 
         let plugin = self.plugin_repo.get(&execution.plugin_id).await?;
@@ -127,44 +259,223 @@ impl ExecutionService {
         Self::ensure_min_anthill_version(&plugin.min_anthill_version)?;
 
         let resolved_params = Self::resolve_parameters(&plugin.parameters, params)?;
-        let mut env = HashMap::new();
-        if !resolved_params.is_empty() {
-            let params_json = serde_json::to_string(&resolved_params).map_err(|e| {
-                AppError::Execution(format!("Failed to serialize parameters: {}", e))
-            })?;
-            env.insert("ANTHILL_PLUGIN_PARAMS".to_string(), params_json);
-        }
+        let stored_params = Self::redact_parameters(&plugin.parameters, &resolved_params)?;
+        let mut env = Self::parameters_env(&resolved_params)?;
         env.insert("ANTHILL_PHASE".to_string(), "apply".to_string());
         if let Some(plan) = execution.preview_payload.clone() {
             env.insert("ANTHILL_PREVIEW_PLAN".to_string(), plan);
         }
 
-        self.exec_repo.begin_apply(id).await?;
+        // 与过期清理和丢弃竞争时以条件更新为准
+        if !self
+            .exec_repo
+            .begin_apply(id, &plugin, stored_params)
+            .await?
+        {
+            return Err(AppError::Execution(
+                "Execution is not ready to apply".to_string(),
+            ));
+        }
 
         let updated_execution = self.exec_repo.get(id).await?;
 
-        self.spawn_process(
-            updated_execution.clone(),
+        self.enqueue(QueuedExecution {
+            execution: updated_execution,
             plugin,
-            ExecutionStatus::Completed,
+            success_status: ExecutionStatus::Completed,
             env,
-            true,
-        )
-        .await?;
+            cleanup_on_success: true,
+        })
+        .await
+    }
 
-        Ok(updated_execution)
+    // 状态写入数据库后才发布
+    pub fn subscribe_statuses(&self) -> broadcast::Receiver<StatusChange> {
+        self.statuses.subscribe()
     }
 
     pub async fn get_execution(&self, id: &str) -> Result<Execution> {
-        self.exec_repo.get(id).await
+        let execution = self.exec_repo.get(id).await?;
+        Ok(self.with_queue_position(execution))
     }
 
     pub async fn list_executions(&self, plugin_id: Option<String>) -> Result<Vec<Execution>> {
-        if let Some(pid) = plugin_id {
-            self.exec_repo.list_by_plugin(&pid).await
+        let executions = if let Some(pid) = plugin_id {
+            self.exec_repo.list_by_plugin(&pid).await?
         } else {
-            self.exec_repo.list_all().await
+            self.exec_repo.list_all().await?
+        };
+        Ok(executions
+            .into_iter()
+            .map(|execution| self.with_queue_position(execution))
+            .collect())
+    }
+
+    pub async fn discard_preview(&self, id: &str) -> Result<Execution> {
+        let execution = self.exec_repo.get(id).await?;
+        if execution.status != ExecutionStatus::PreviewReady
+            || !self.close_preview(id, ExecutionStatus::Stopped).await?
+        {
+            return Err(AppError::Execution(
+                "Only previews waiting to be applied can be discarded".to_string(),
+            ));
+        }
+        self.get_execution(id).await
+    }
+
+    pub fn spawn_preview_sweeper(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(PREVIEW_SWEEP_INTERVAL);
+            loop {
+                ticker.tick().await;
+                match service.expire_previews().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("Expired {} unapplied preview(s)", count),
+                    Err(err) => tracing::error!("Failed to expire previews: {}", err),
+                }
+            }
+        });
+    }
+
+    async fn expire_previews(&self) -> Result<usize> {
+        let now = Utc::now().timestamp_millis();
+        let mut expired = 0;
+        for id in self.exec_repo.list_expired_previews(now).await? {
+            if self.close_preview(&id, ExecutionStatus::Expired).await? {
+                expired += 1;
+            }
+        }
+        Ok(expired)
+    }
+
+    async fn close_preview(&self, id: &str, status: ExecutionStatus) -> Result<bool> {
+        if !self.exec_repo.close_preview(id, status).await? {
+            return Ok(false);
+        }
+        self.statuses.publish(id, status);
+        // 预览阶段的产物已在进程结束时收集，工作目录只为 apply 保留
+        let removed = Self::work_dir_for(id).and_then(|dir| remove_dir_if_exists(&dir));
+        if let Err(err) = removed {
+            tracing::warn!("Failed to remove work dir of preview {}: {}", id, err);
+        }
+        Ok(true)
+    }
+
+    pub fn spawn_dispatcher(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                service.queue.slot_freed().await;
+                service.dispatch_queued().await;
+            }
+        });
+    }
+
+    // 先回放缓冲的输出再推送实时行，已结束的执行回放数据库中的输出
+    pub async fn follow_logs(&self, id: &str) -> Result<mpsc::Receiver<LogEvent>> {
+        let subscription = self.logs.subscribe(id);
+        let execution = self.exec_repo.get(id).await?;
+        let exec_repo = self.exec_repo.clone();
+        let (tx, rx) = mpsc::channel(64);
+
+        tokio::spawn(async move {
+            let execution = match subscription {
+                Some(LogSubscription {
+                    history,
+                    progress,
+                    mut receiver,
+                }) => {
+                    let replay = history
+                        .into_iter()
+                        .map(LogEvent::Log)
+                        .chain(progress.map(LogEvent::Progress));
+                    for event in replay {
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    loop {
+                        match receiver.recv().await {
+                            Ok(event) => {
+                                if tx.send(event).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                tracing::warn!(
+                                    "Log subscriber for execution {} skipped {} events",
+                                    execution.id,
+                                    skipped
+                                );
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    match exec_repo.get(&execution.id).await {
+                        Ok(execution) => execution,
+                        Err(_) => return,
+                    }
+                }
+                None => {
+                    let progress = Some(execution.progress.clone())
+                        .filter(|progress| !progress.is_empty())
+                        .map(LogEvent::Progress);
+                    let replay = Self::stored_log_lines(&execution)
+                        .into_iter()
+                        .map(LogEvent::Log)
+                        .chain(progress);
+                    for event in replay {
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    execution
+                }
+            };
+            let _ = tx
+                .send(LogEvent::End {
+                    status: execution.status,
+                    exit_code: execution.exit_code,
+                })
+                .await;
+        });
+
+        Ok(rx)
+    }
+
+    // 没有日志文件的执行回退到数据库中保存的输出
+    pub async fn read_logs(
+        &self,
+        id: &str,
+        stream: LogStream,
+        offset: u64,
+        limit: Option<usize>,
+    ) -> Result<LogChunk> {
+        let execution = self.exec_repo.get(id).await?;
+        if let Some(chunk) = log_store::read(id, stream, offset, limit).await? {
+            return Ok(chunk);
         }
+        let stored = match stream {
+            LogStream::Stdout => execution.stdout,
+            LogStream::Stderr => execution.stderr,
+        };
+        Ok(log_store::read_stored(
+            stream,
+            stored.as_deref().unwrap_or_default(),
+            offset,
+            limit,
+        ))
+    }
+
+    pub async fn list_artifacts(&self, id: &str) -> Result<Vec<Artifact>> {
+        self.exec_repo.get(id).await?;
+        ArtifactStore::list(id)
+    }
+
+    pub async fn find_artifact(&self, id: &str, name: &str) -> Result<(Artifact, PathBuf)> {
+        self.exec_repo.get(id).await?;
+        ArtifactStore::find(id, name)
     }
 
     pub async fn wait_for_states(
@@ -173,56 +484,352 @@ impl ExecutionService {
         targets: &[ExecutionStatus],
         timeout_ms: u64,
     ) -> Result<Execution> {
-        let deadline = Utc::now().timestamp_millis() + timeout_ms as i64;
+        // 先订阅再读库，避免错过两者之间发生的状态变化
+        let mut changes = self.statuses.subscribe();
+        let current = self.get_execution(id).await?;
+        if targets.contains(&current.status) {
+            return Ok(current);
+        }
+
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
         loop {
-            let current = self.exec_repo.get(id).await?;
-            if targets.iter().any(|t| *t == current.status) {
-                return Ok(current);
-            }
-            if Utc::now().timestamp_millis() > deadline {
-                return Ok(current);
+            match timeout_at(deadline, changes.recv()).await {
+                Err(_) | Ok(Err(broadcast::error::RecvError::Closed)) => {
+                    return self.get_execution(id).await;
+                }
+                Ok(Ok(change))
+                    if change.execution_id != id || !targets.contains(&change.status) => {}
+                // 落后时可能漏掉了目标状态，重新读库确认
+                Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => {
+                    let current = self.get_execution(id).await?;
+                    if targets.contains(&current.status) {
+                        return Ok(current);
+                    }
+                }
             }
-            sleep(Duration::from_millis(100)).await;
         }
     }
 
-    pub async fn stop_execution(&self, id: &str) -> Result<()> {
+    pub async fn delete_execution(&self, id: &str) -> Result<()> {
         let execution = self.exec_repo.get(id).await?;
+        if matches!(
+            execution.status,
+            ExecutionStatus::Pending
+                | ExecutionStatus::Running
+                | ExecutionStatus::Applying
+                | ExecutionStatus::AwaitingInput
+        ) {
+            return Err(AppError::Execution(
+                "Execution is still running, stop it first".to_string(),
+            ));
+        }
 
-        if let Some(pid) = execution.pid {
-            // Try to kill the process
-            // TODO: Implement proper process management
-            tracing::info!("Stopping execution {} with pid {}", id, pid);
+        if !self.exec_repo.delete(id).await? {
+            return Err(AppError::Execution(
+                "Execution belongs to a workflow run, delete the workflow to remove it".to_string(),
+            ));
         }
+        retention::remove_execution_files(id);
+        Ok(())
+    }
 
-        self.exec_repo
-            .update_status(id, ExecutionStatus::Stopped)
+    pub async fn delete_executions(
+        &self,
+        plugin_id: Option<&str>,
+        before: Option<i64>,
+    ) -> Result<usize> {
+        if plugin_id.is_none() && before.is_none() {
+            return Err(AppError::Execution(
+                "Specify plugin_id or before to delete executions".to_string(),
+            ));
+        }
+
+        let before = before.unwrap_or(i64::MAX);
+        let ids = self
+            .exec_repo
+            .list_finished_before(before, plugin_id, None)
             .await?;
+        let mut deleted = 0;
+        for id in ids {
+            if self.exec_repo.delete(&id).await? {
+                deleted += 1;
+            }
+            retention::remove_execution_files(&id);
+        }
+        Ok(deleted)
+    }
 
-        Ok(())
+    pub async fn provide_input(&self, id: &str, value: Option<serde_json::Value>) -> Result<()> {
+        let execution = self.exec_repo.get(id).await?;
+        let request = match (&execution.status, &execution.input_request) {
+            (ExecutionStatus::AwaitingInput, Some(raw)) => {
+                serde_json::from_str::<InputRequest>(raw).map_err(|e| {
+                    AppError::Execution(format!("Invalid stored input request: {}", e))
+                })?
+            }
+            _ => {
+                return Err(AppError::Execution(
+                    "Execution is not waiting for input".to_string(),
+                ));
+            }
+        };
+
+        let parameter = &request.parameter;
+        let value = value
+            .or_else(|| parameter.default.clone())
+            .ok_or_else(|| AppError::Execution(format!("Missing input: {}", parameter.name)))?;
+        if !parameter.param_type.matches(&value) {
+            return Err(AppError::Execution(format!(
+                "Input '{}' does not match type {:?}",
+                parameter.name, parameter.param_type
+            )));
+        }
+        Self::ensure_choice(parameter, &value)?;
+
+        // 先恢复状态再写入 stdin，避免覆盖插件紧接着发出的下一个输入请求
+        if !self.exec_repo.answer_input(id).await? {
+            return Err(AppError::Execution(
+                "Execution is not waiting for input".to_string(),
+            ));
+        }
+        self.inputs.set_waiting(id, false);
+        self.statuses.publish(id, ExecutionStatus::Running);
+        let answer = serde_json::json!({ "name": parameter.name, "value": value });
+        self.inputs.send(id, &answer.to_string()).await
     }
 
-    async fn start_process(
-        &self,
-        plugin: crate::models::Plugin,
-        phase: ExecutionPhase,
-        success_status: ExecutionStatus,
-        env: HashMap<String, String>,
-        cleanup_on_success: bool,
-    ) -> Result<Execution> {
-        let execution = self
+    pub async fn stop_execution(&self, id: &str) -> Result<()> {
+        // 仍在排队的执行直接出队
+        if self.queue.remove(id).is_some() {
+            self.logs.close(id);
+            self.exec_repo
+                .update_result(id, None, None, None, ExecutionStatus::Stopped)
+                .await?;
+            self.statuses.publish(id, ExecutionStatus::Stopped);
+            return Ok(());
+        }
+
+        // 进程仍在运行时由监督任务终止进程树并记录 Stopped
+        if self.processes.stop(id).await {
+            return Ok(());
+        }
+
+        // 没有登记进程的运行中执行正在收尾，由监督任务写入真实结果；
+        // 只有尚未启动的执行可以直接记为 Stopped
+        self.exec_repo.get(id).await?;
+        if self
             .exec_repo
-            .create_with_phase(&plugin.plugin_id, phase)
+            .finish_if(id, ExecutionStatus::Stopped, &[ExecutionStatus::Pending])
+            .await?
+        {
+            self.statuses.publish(id, ExecutionStatus::Stopped);
+            return Ok(());
+        }
+        Err(AppError::Execution("Execution is not running".to_string()))
+    }
+
+    pub async fn recover_orphans(&self, policy: OrphanPolicy) -> Result<()> {
+        let unfinished = self
+            .exec_repo
+            .list_by_statuses(&[
+                ExecutionStatus::Pending,
+                ExecutionStatus::Running,
+                ExecutionStatus::Applying,
+                ExecutionStatus::AwaitingInput,
+            ])
             .await?;
-        self.spawn_process(
-            execution.clone(),
-            plugin,
-            success_status,
-            env,
-            cleanup_on_success,
-        )
-        .await?;
-        Ok(execution)
+
+        let mut keep = HashSet::new();
+        for execution in unfinished {
+            let live_pid = execution
+                .pid
+                .and_then(|pid| u32::try_from(pid).ok())
+                .filter(|pid| process_registry::is_alive(*pid));
+
+            match live_pid {
+                None => {
+                    tracing::warn!("Execution {} was interrupted by restart", execution.id);
+                    self.exec_repo
+                        .mark_interrupted(&execution.id, INTERRUPTED_REASON)
+                        .await?;
+                }
+                // 预览输出随旧进程的管道丢失，只接管 apply 阶段
+                Some(pid)
+                    if policy == OrphanPolicy::Adopt
+                        && execution.phase == ExecutionPhase::Apply =>
+                {
+                    tracing::info!("Adopting execution {} with pid {}", execution.id, pid);
+                    keep.insert(execution.id.clone());
+                    self.adopt_orphan(execution.id, pid);
+                }
+                Some(pid) => {
+                    tracing::warn!(
+                        "Killing execution {} with pid {} left by previous run",
+                        execution.id,
+                        pid
+                    );
+                    process_registry::terminate_orphan(
+                        pid,
+                        process_registry::TERMINATE_GRACE_PERIOD,
+                    )
+                    .await;
+                    self.exec_repo
+                        .mark_interrupted(
+                            &execution.id,
+                            &format!("{}, process was killed", INTERRUPTED_REASON),
+                        )
+                        .await?;
+                }
+            }
+        }
+
+        let previews = self
+            .exec_repo
+            .list_by_statuses(&[ExecutionStatus::PreviewReady])
+            .await?;
+        keep.extend(previews.into_iter().map(|execution| execution.id));
+
+        Self::remove_stale_work_dirs(&keep).await
+    }
+
+    // 旧进程不是当前服务的子进程，只能轮询等待它退出
+    fn adopt_orphan(&self, exec_id: String, pid: u32) {
+        let stop_rx = self.processes.register(&exec_id, pid);
+        let exec_repo = self.exec_repo.clone();
+        let processes = self.processes.clone();
+        let statuses = self.statuses.clone();
+
+        tokio::spawn(async move {
+            let mut stop_ack = None;
+            tokio::select! {
+                _ = process_registry::wait_for_exit(pid) => {}
+                Ok(ack) = stop_rx => {
+                    stop_ack = Some(ack);
+                    process_registry::terminate_orphan(
+                        pid,
+                        process_registry::TERMINATE_GRACE_PERIOD,
+                    )
+                    .await;
+                }
+            }
+
+            // 旧进程的退出码和输出已无法获取，结果未知时不能按成功记录
+            let (status, note) = if stop_ack.is_some() {
+                (ExecutionStatus::Stopped, "Stopped after restart")
+            } else {
+                (
+                    ExecutionStatus::Failed,
+                    "Process exited after restart, exit status unknown",
+                )
+            };
+            if exec_repo
+                .update_result(&exec_id, None, Some(note.to_string()), None, status)
+                .await
+                .is_ok()
+            {
+                statuses.publish(&exec_id, status);
+            }
+            if let Ok(work_dir) = Self::work_dir_for(&exec_id) {
+                Self::collect_artifacts(&exec_id, &work_dir).await;
+                let _ = std::fs::remove_dir_all(work_dir);
+            }
+
+            processes.unregister(&exec_id);
+            if let Some(ack) = stop_ack {
+                let _ = ack.send(());
+            }
+        });
+    }
+
+    async fn remove_stale_work_dirs(keep: &HashSet<String>) -> Result<()> {
+        let base_dir = paths::work_dir()?;
+        let entries = match std::fs::read_dir(&base_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if keep.contains(&name) || !entry.path().is_dir() {
+                continue;
+            }
+            tracing::info!("Removing stale work dir {}", entry.path().display());
+            Self::collect_artifacts(&name, &entry.path()).await;
+            if let Err(err) = std::fs::remove_dir_all(entry.path()) {
+                tracing::warn!(
+                    "Failed to remove work dir {}: {}",
+                    entry.path().display(),
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn enqueue(&self, job: QueuedExecution) -> Result<Execution> {
+        let id = job.execution.id.clone();
+        self.logs.open(&id);
+        self.queue.push(job);
+        if let Some(err) = self.dispatch_queued().await.remove(&id) {
+            return Err(err);
+        }
+        self.get_execution(&id).await
+    }
+
+    // 返回启动失败的执行及其错误
+    async fn dispatch_queued(&self) -> HashMap<String, AppError> {
+        let mut failures = HashMap::new();
+        while let Some(job) = self.queue.pop_ready() {
+            let QueuedExecution {
+                execution,
+                plugin,
+                success_status,
+                env,
+                cleanup_on_success,
+            } = job;
+            let plugin_id = plugin.plugin_id.clone();
+            let started = self
+                .spawn_process(
+                    execution.clone(),
+                    plugin,
+                    success_status,
+                    env,
+                    cleanup_on_success,
+                )
+                .await;
+            if !matches!(started, Ok(true)) {
+                self.queue.release(&plugin_id);
+                self.logs.close(&execution.id);
+            }
+            if let Ok(false) = started {
+                tracing::info!("Execution {} was stopped before it started", execution.id);
+            } else if let Err(err) = started {
+                tracing::error!("Failed to start execution {}: {}", execution.id, err);
+                self.exec_repo
+                    .update_result(
+                        &execution.id,
+                        None,
+                        Some(err.to_string()),
+                        None,
+                        ExecutionStatus::Failed,
+                    )
+                    .await
+                    .ok();
+                self.statuses
+                    .publish(&execution.id, ExecutionStatus::Failed);
+                failures.insert(execution.id, err);
+            }
+        }
+        failures
+    }
+
+    fn with_queue_position(&self, mut execution: Execution) -> Execution {
+        if execution.status == ExecutionStatus::Pending {
+            execution.queue_position = self.queue.position(&execution.id);
+        }
+        execution
     }
 
     async fn spawn_process(
@@ -230,23 +837,31 @@ impl ExecutionService {
         execution: Execution,
         plugin: crate::models::Plugin,
         success_status: ExecutionStatus,
-        env: HashMap<String, String>,
+        mut env: HashMap<String, String>,
         cleanup_on_success: bool,
-    ) -> Result<()> {
+    ) -> Result<bool> {
+        // 出队后、登记进程前收到的停止请求已把记录改为 Stopped，不再启动
+        if self.exec_repo.get(&execution.id).await?.status != ExecutionStatus::Pending {
+            return Ok(false);
+        }
         let work_dir = Self::work_dir_for(&execution.id)?;
-        std::fs::create_dir_all(&work_dir)?;
+        let output_dir = ArtifactStore::output_dir(&work_dir);
+        std::fs::create_dir_all(&output_dir)?;
+        env.insert(
+            "ANTHILL_OUTPUT_DIR".to_string(),
+            output_dir.to_string_lossy().to_string(),
+        );
 
-        let exec_result = match plugin.plugin_type {
-            crate::models::PluginType::Python => {
-                self.python_executor
-                    .execute(&plugin, Vec::new(), env, &work_dir)
-                    .await
-            }
-            crate::models::PluginType::JavaScript => {
-                self.node_executor
-                    .execute(&plugin, Vec::new(), env, &work_dir)
-                    .await
-            }
+        let runtime = plugin.runtime_name().unwrap_or_default();
+        let exec_result = match self.executors.get(runtime) {
+            Some(executor) => match executor.check_available() {
+                Ok(()) => executor.execute(&plugin, Vec::new(), env, &work_dir).await,
+                Err(err) => Err(err),
+            },
+            None => Err(AppError::Execution(format!(
+                "No executor registered for runtime '{}'",
+                runtime
+            ))),
         };
 
         let (pid, mut child) = match exec_result {
@@ -257,32 +872,94 @@ impl ExecutionService {
             }
         };
 
-        self.exec_repo.update_pid(&execution.id, pid).await?;
+        let stop_rx = self.processes.register(&execution.id, pid);
+        let marked_running = self.exec_repo.update_pid(&execution.id, pid).await;
+        if !matches!(marked_running, Ok(true)) {
+            self.processes.unregister(&execution.id);
+            let _ = process_registry::terminate_tree(&mut child, pid, Duration::ZERO).await;
+            let _ = std::fs::remove_dir_all(&work_dir);
+            return marked_running;
+        }
+        self.statuses
+            .publish(&execution.id, ExecutionStatus::Running);
+        if let Some(stdin) = child.stdin.take() {
+            self.inputs.attach(&execution.id, stdin).await;
+        }
 
         let exec_id = execution.id.clone();
         let exec_repo_clone = self.exec_repo.clone();
+        let processes = self.processes.clone();
+        let logs = self.logs.clone();
+        let inputs = self.inputs.clone();
+        let statuses = self.statuses.clone();
+        let queue = self.queue.clone();
+        let plugin_id = plugin.plugin_id.clone();
+        let timeout = self.timeout_for(&plugin, execution.phase);
         let keep_on_success =
             !cleanup_on_success && success_status == ExecutionStatus::PreviewReady;
 
+        let waiting = self.inputs.watch_waiting(&execution.id);
+        let pipes = PipeContext {
+            execution_id: execution.id.clone(),
+            exec_repo: self.exec_repo.clone(),
+            logs: self.logs.clone(),
+            inputs: self.inputs.clone(),
+            statuses: self.statuses.clone(),
+            limits: self.limits.logs.clone(),
+        };
+
         tokio::spawn(async move {
-            let mut stdout_child = child.stdout.take();
-            let mut stderr_child = child.stderr.take();
+            let stdout_reader = tokio::spawn(Self::drain_pipe(
+                child.stdout.take(),
+                LogStream::Stdout,
+                pipes.clone(),
+            ));
+            let stderr_reader = tokio::spawn(Self::drain_pipe(
+                child.stderr.take(),
+                LogStream::Stderr,
+                pipes,
+            ));
 
-            let status_result = child.wait().await;
+            let mut termination = Termination::Exited;
+            let status_result = tokio::select! {
+                status = child.wait() => status,
+                Ok(ack) = stop_rx => {
+                    termination = Termination::Stopped(ack);
+                    process_registry::terminate_tree(
+                        &mut child,
+                        pid,
+                        process_registry::TERMINATE_GRACE_PERIOD,
+                    )
+                    .await
+                }
+                Some(limit) = Self::deadline(timeout, waiting) => {
+                    tracing::warn!("Execution {} timed out after {:?}", exec_id, limit);
+                    termination = Termination::TimedOut(limit);
+                    process_registry::terminate_tree(
+                        &mut child,
+                        pid,
+                        process_registry::TERMINATE_GRACE_PERIOD,
+                    )
+                    .await
+                }
+            };
+            let interrupted = !matches!(termination, Termination::Exited);
+            Self::collect_artifacts(&exec_id, &work_dir).await;
 
             match status_result {
                 Ok(status) => {
                     let exit_code = status.code();
 
-                    use tokio::io::AsyncReadExt;
-                    let mut stdout_buf = String::new();
-                    let mut stderr_buf = String::new();
-
-                    if let Some(ref mut stdout) = stdout_child {
-                        let _ = stdout.read_to_string(&mut stdout_buf).await;
-                    }
-                    if let Some(ref mut stderr) = stderr_child {
-                        let _ = stderr.read_to_string(&mut stderr_buf).await;
+                    let (stdout_buf, plugin_output) = stdout_reader.await.unwrap_or_default();
+                    let (mut stderr_buf, _) = stderr_reader.await.unwrap_or_default();
+                    if let Termination::TimedOut(limit) = &termination {
+                        if !stderr_buf.is_empty() && !stderr_buf.ends_with('\n') {
+                            stderr_buf.push('\n');
+                        }
+                        stderr_buf.push_str(&format!(
+                            "Execution timed out after {} seconds\n",
+                            limit.as_secs()
+                        ));
                     }
 
                     let stdout = if !stdout_buf.is_empty() {
@@ -297,7 +974,27 @@ impl ExecutionService {
                         None
                     };
 
-                    if exit_code == Some(0) && success_status == ExecutionStatus::PreviewReady {
+                    // 插件发出 error 事件时即使退出码为 0 也算失败
+                    let succeeded = exit_code == Some(0) && plugin_output.error.is_none();
+                    let preview_ready = !interrupted
+                        && succeeded
+                        && success_status == ExecutionStatus::PreviewReady;
+                    let preview_payload = if preview_ready {
+                        plugin_output.preview_payload()
+                    } else {
+                        None
+                    };
+                    exec_repo_clone
+                        .update_output(
+                            &exec_id,
+                            preview_payload,
+                            plugin_output.result,
+                            plugin_output.error,
+                        )
+                        .await
+                        .ok();
+
+                    if preview_ready {
                         let confirm_token = uuid::Uuid::new_v4().to_string();
                         let expires_at = Utc::now().timestamp_millis() + PREVIEW_TTL_MS;
                         exec_repo_clone
@@ -311,25 +1008,27 @@ impl ExecutionService {
                             )
                             .await
                             .ok();
+                        statuses.publish(&exec_id, ExecutionStatus::PreviewReady);
                         if !keep_on_success {
                             let _ = std::fs::remove_dir_all(&work_dir);
                         }
-                        return;
-                    }
-
-                    let exec_status = if exit_code == Some(0) {
-                        success_status
                     } else {
-                        ExecutionStatus::Failed
-                    };
+                        let exec_status = match &termination {
+                            Termination::Stopped(_) => ExecutionStatus::Stopped,
+                            Termination::TimedOut(_) => ExecutionStatus::TimedOut,
+                            Termination::Exited if succeeded => success_status,
+                            Termination::Exited => ExecutionStatus::Failed,
+                        };
 
-                    exec_repo_clone
-                        .update_result(&exec_id, stdout, stderr, exit_code, exec_status)
-                        .await
-                        .ok();
+                        exec_repo_clone
+                            .update_result(&exec_id, stdout, stderr, exit_code, exec_status)
+                            .await
+                            .ok();
+                        statuses.publish(&exec_id, exec_status);
 
-                    if exit_code != Some(0) || cleanup_on_success {
-                        if let Err(e) = std::fs::remove_dir_all(&work_dir) {
+                        if (interrupted || !succeeded || cleanup_on_success)
+                            && let Err(e) = std::fs::remove_dir_all(&work_dir)
+                        {
                             tracing::warn!(
                                 "Failed to remove work dir {}: {}",
                                 work_dir.display(),
@@ -340,24 +1039,324 @@ impl ExecutionService {
                 }
                 Err(e) => {
                     tracing::error!("Error waiting for process: {}", e);
+                    let exec_status = match &termination {
+                        Termination::Stopped(_) => ExecutionStatus::Stopped,
+                        Termination::TimedOut(_) => ExecutionStatus::TimedOut,
+                        Termination::Exited => ExecutionStatus::Failed,
+                    };
                     exec_repo_clone
                         .update_result(
                             &exec_id,
                             None,
                             Some(format!("Error: {}", e)),
                             None,
-                            ExecutionStatus::Failed,
+                            exec_status,
                         )
                         .await
                         .ok();
+                    statuses.publish(&exec_id, exec_status);
                     if let Err(err) = std::fs::remove_dir_all(&work_dir) {
                         tracing::warn!("Failed to remove work dir {}: {}", work_dir.display(), err);
                     }
                 }
             }
+
+            processes.unregister(&exec_id);
+            inputs.detach(&exec_id).await;
+            logs.close(&exec_id);
+            queue.release(&plugin_id);
+            if let Termination::Stopped(ack) = termination {
+                let _ = ack.send(());
+            }
         });
 
-        Ok(())
+        Ok(true)
+    }
+
+    fn timeout_for(
+        &self,
+        plugin: &crate::models::Plugin,
+        phase: ExecutionPhase,
+    ) -> Option<Duration> {
+        let (declared, default) = match phase {
+            ExecutionPhase::Prepare => (
+                plugin.prepare_timeout_seconds,
+                self.limits.prepare_timeout_seconds,
+            ),
+            ExecutionPhase::Apply => (
+                plugin.apply_timeout_seconds,
+                self.limits.apply_timeout_seconds,
+            ),
+        };
+        declared
+            .and_then(|seconds| u64::try_from(seconds).ok())
+            .or(default)
+            .map(Duration::from_secs)
+    }
+
+    async fn deadline(
+        timeout: Option<Duration>,
+        mut waiting: watch::Receiver<bool>,
+    ) -> Option<Duration> {
+        let Some(limit) = timeout else {
+            return std::future::pending().await;
+        };
+        // 等待用户输入的时间不计入超时
+        let mut remaining = limit;
+        loop {
+            if *waiting.borrow_and_update() {
+                if waiting.changed().await.is_err() {
+                    return std::future::pending().await;
+                }
+                continue;
+            }
+            let started = Instant::now();
+            tokio::select! {
+                _ = sleep(remaining) => return Some(limit),
+                changed = waiting.changed() => {
+                    remaining = remaining.saturating_sub(started.elapsed());
+                    if changed.is_err() {
+                        sleep(remaining).await;
+                        return Some(limit);
+                    }
+                }
+            }
+        }
+    }
+
+    // stdout 中的协议事件不作为文本保存（log 事件除外），返回截断后写入数据库的输出
+    async fn drain_pipe<R>(
+        pipe: Option<R>,
+        stream: LogStream,
+        context: PipeContext,
+    ) -> (String, PluginOutput)
+    where
+        R: AsyncRead + Unpin,
+    {
+        let PipeContext {
+            execution_id,
+            exec_repo,
+            logs,
+            inputs,
+            statuses,
+            limits,
+        } = context;
+        let mut plugin_output = PluginOutput::default();
+        let mut capture = TruncatedCapture::new(limits.max_db_bytes);
+        let Some(pipe) = pipe else {
+            return (capture.finish(), plugin_output);
+        };
+
+        let mut writer = match LogWriter::open(&execution_id, stream, limits).await {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to open {} log file of execution {}: {}",
+                    stream.as_str(),
+                    execution_id,
+                    e
+                );
+                None
+            }
+        };
+
+        let (progress_tx, progress_rx) = watch::channel(None);
+        let progress_writer = tokio::spawn(Self::write_progress(
+            exec_repo.clone(),
+            execution_id.clone(),
+            progress_rx,
+        ));
+
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) => break,
+                Ok(_) => {
+                    let chunk = String::from_utf8_lossy(&buf);
+                    let line = chunk.trim_end_matches(['\n', '\r']).to_string();
+                    let event = match stream {
+                        LogStream::Stdout => plugin_protocol::parse_event(&line),
+                        LogStream::Stderr => None,
+                    };
+                    let Some(event) = event else {
+                        if stream == LogStream::Stdout {
+                            plugin_output.record_plain(&chunk);
+                        }
+                        capture.push(&chunk);
+                        Self::write_log(&mut writer, &execution_id, chunk.as_bytes()).await;
+                        logs.push(&execution_id, stream, line);
+                        continue;
+                    };
+
+                    plugin_output.record(&event);
+                    if let Some(progress) = event.progress() {
+                        progress_tx.send_replace(Some(progress.clone()));
+                        logs.push_progress(&execution_id, progress);
+                    }
+                    if let PluginEvent::InputRequest(request) = &event {
+                        Self::record_input_request(
+                            &exec_repo,
+                            &logs,
+                            &inputs,
+                            &statuses,
+                            &execution_id,
+                            request,
+                        )
+                        .await;
+                    }
+                    if let Some(text) = plugin_protocol::log_text(&event) {
+                        let entry = format!("{}\n", text);
+                        capture.push(&entry);
+                        Self::write_log(&mut writer, &execution_id, entry.as_bytes()).await;
+                        logs.push(&execution_id, stream, text);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to read {:?} of execution {}: {}",
+                        stream,
+                        execution_id,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        if let Some(writer) = writer.as_mut()
+            && let Err(e) = writer.flush().await
+        {
+            tracing::warn!("Failed to flush log of execution {}: {}", execution_id, e);
+        }
+        // 最后的进度写入完成后再返回，保证它早于执行结果落库
+        drop(progress_tx);
+        let _ = progress_writer.await;
+        (capture.finish(), plugin_output)
+    }
+
+    async fn write_progress(
+        exec_repo: ExecutionRepository,
+        execution_id: String,
+        mut progress: watch::Receiver<Option<ExecutionProgress>>,
+    ) {
+        let mut open = true;
+        let mut changed = false;
+        loop {
+            if !changed && (!open || progress.changed().await.is_err()) {
+                return;
+            }
+            let latest = progress.borrow_and_update().clone();
+            if let Some(latest) = latest
+                && let Err(e) = exec_repo.update_progress(&execution_id, &latest).await
+            {
+                tracing::warn!(
+                    "Failed to record progress of execution {}: {}",
+                    execution_id,
+                    e
+                );
+            }
+            changed = false;
+            if !open {
+                return;
+            }
+            // 输出结束时不再等待，立即写入最后的值
+            let until = Instant::now() + PROGRESS_WRITE_INTERVAL;
+            loop {
+                match timeout_at(until, progress.changed()).await {
+                    Ok(Ok(())) => changed = true,
+                    Ok(Err(_)) => {
+                        open = false;
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+
+    async fn record_input_request(
+        exec_repo: &ExecutionRepository,
+        logs: &LogHub,
+        inputs: &InputChannels,
+        statuses: &StatusWatch,
+        execution_id: &str,
+        request: &InputRequest,
+    ) {
+        let stored = serde_json::to_string(request).map_err(|e| AppError::Execution(e.to_string()));
+        if let Err(e) = match stored {
+            Ok(stored) => exec_repo.request_input(execution_id, &stored).await,
+            Err(e) => Err(e),
+        } {
+            tracing::warn!(
+                "Failed to record input request of execution {}: {}",
+                execution_id,
+                e
+            );
+            return;
+        }
+        inputs.set_waiting(execution_id, true);
+        statuses.publish(execution_id, ExecutionStatus::AwaitingInput);
+        logs.notify(execution_id, LogEvent::InputRequest(request.clone()));
+    }
+
+    // 写入失败后不再写日志文件
+    async fn write_log(writer: &mut Option<LogWriter>, execution_id: &str, bytes: &[u8]) {
+        let Some(log) = writer.as_mut() else {
+            return;
+        };
+        if let Err(e) = log.write(bytes).await {
+            tracing::warn!(
+                "Failed to write log file of execution {}, disabling it: {}",
+                execution_id,
+                e
+            );
+            *writer = None;
+        }
+    }
+
+    fn stored_log_lines(execution: &Execution) -> Vec<LogLine> {
+        let timestamp = execution.finished_at.unwrap_or(execution.started_at);
+        let stdout = execution
+            .stdout
+            .iter()
+            .flat_map(|output| output.lines())
+            .map(|line| (LogStream::Stdout, line));
+        let stderr = execution
+            .stderr
+            .iter()
+            .flat_map(|output| output.lines())
+            .map(|line| (LogStream::Stderr, line));
+        stdout
+            .chain(stderr)
+            .map(|(stream, line)| LogLine {
+                stream,
+                line: line.to_string(),
+                timestamp,
+            })
+            .collect()
+    }
+
+    async fn collect_artifacts(execution_id: &str, work_dir: &std::path::Path) {
+        // 输出文件可能很大，复制放到阻塞线程池执行
+        let (id, dir) = (execution_id.to_string(), work_dir.to_path_buf());
+        let collected = tokio::task::spawn_blocking(move || ArtifactStore::collect(&id, &dir))
+            .await
+            .unwrap_or_else(|e| Err(AppError::Execution(e.to_string())));
+        match collected {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(
+                "Collected {} artifact(s) from execution {}",
+                count,
+                execution_id
+            ),
+            Err(e) => tracing::warn!(
+                "Failed to collect artifacts of execution {}: {}",
+                execution_id,
+                e
+            ),
+        }
     }
 
     fn work_dir_for(execution_id: &str) -> Result<PathBuf> {
@@ -365,6 +1364,39 @@ impl ExecutionService {
         Ok(base_dir.join(execution_id))
     }
 
+    fn parameters_env(
+        resolved: &HashMap<String, serde_json::Value>,
+    ) -> Result<HashMap<String, String>> {
+        let mut env = HashMap::new();
+        if !resolved.is_empty() {
+            let params_json = serde_json::to_string(resolved).map_err(|e| {
+                AppError::Execution(format!("Failed to serialize parameters: {}", e))
+            })?;
+            env.insert("ANTHILL_PLUGIN_PARAMS".to_string(), params_json);
+        }
+        Ok(env)
+    }
+
+    // 执行上保存的参数，密钥值被遮盖
+    fn redact_parameters(
+        raw_parameters: &Option<String>,
+        resolved: &HashMap<String, serde_json::Value>,
+    ) -> Result<Option<String>> {
+        if resolved.is_empty() {
+            return Ok(None);
+        }
+        let schema = Self::parse_parameters(raw_parameters)?;
+        let mut redacted = resolved.clone();
+        for param in schema.iter().filter(|param| param.is_secret()) {
+            if let Some(value) = redacted.get_mut(&param.name) {
+                *value = serde_json::Value::String(REDACTED_VALUE.to_string());
+            }
+        }
+        serde_json::to_string(&redacted)
+            .map(Some)
+            .map_err(|e| AppError::Execution(format!("Failed to serialize parameters: {}", e)))
+    }
+
     fn resolve_parameters(
         raw_parameters: &Option<String>,
         provided: HashMap<String, serde_json::Value>,
@@ -465,10 +1497,7 @@ impl ExecutionService {
             return Ok(());
         }
 
-        if choices
-            .iter()
-            .any(|choice| matches_choice(choice, value))
-        {
+        if choices.iter().any(|choice| matches_choice(choice, value)) {
             return Ok(());
         }
 
@@ -0,0 +1,595 @@
+use crate::error::{AppError, Result};
+use crate::models::{FileWatch, PluginParamType, WatchEvent};
+use crate::repository::FileWatchRepository;
+use crate::services::execution_service::ExecutionService;
+use chrono::Utc;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant, sleep_until};
+use uuid::Uuid;
+
+const MAX_DEBOUNCE_MS: i64 = 600_000;
+
+#[derive(Debug, Clone)]
+pub struct FileWatchSettings {
+    pub name: String,
+    pub plugin_id: String,
+    pub path: String,
+    pub recursive: bool,
+    pub patterns: Vec<String>,
+    pub events: Vec<WatchEvent>,
+    pub debounce_ms: i64,
+    pub path_param: String,
+    pub params: HashMap<String, Value>,
+    pub enabled: bool,
+}
+
+// 丢弃时停止系统监听和事件循环
+struct ActiveWatch {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for ActiveWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct WatchFilter {
+    root: PathBuf,
+    events: Vec<WatchEvent>,
+    // 不含 / 的模式匹配文件名
+    names: GlobSet,
+    // 含 / 的模式匹配相对监听目录的路径
+    paths: GlobSet,
+    any: bool,
+    directory_param: bool,
+}
+
+impl WatchFilter {
+    fn new(watch: &FileWatch, param_type: PluginParamType) -> Result<Self> {
+        let patterns: Vec<String> = serde_json::from_str(&watch.patterns)
+            .map_err(|e| AppError::Watch(format!("Invalid watch patterns: {}", e)))?;
+        let events: Vec<WatchEvent> = serde_json::from_str(&watch.events)
+            .map_err(|e| AppError::Watch(format!("Invalid watch events: {}", e)))?;
+
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+        for pattern in &patterns {
+            let glob = Glob::new(pattern).map_err(|e| {
+                AppError::Watch(format!("Invalid glob pattern '{}': {}", pattern, e))
+            })?;
+            if pattern.contains('/') {
+                paths.add(glob);
+            } else {
+                names.add(glob);
+            }
+        }
+        let build = |builder: GlobSetBuilder| {
+            builder
+                .build()
+                .map_err(|e| AppError::Watch(format!("Invalid glob patterns: {}", e)))
+        };
+
+        Ok(Self {
+            root: PathBuf::from(&watch.path),
+            events,
+            names: build(names)?,
+            paths: build(paths)?,
+            any: patterns.is_empty(),
+            directory_param: param_type == PluginParamType::Directory,
+        })
+    }
+
+    fn accepts(&self, path: &Path, event: WatchEvent) -> bool {
+        if !self.events.contains(&event) {
+            return false;
+        }
+        // 只关心文件，子目录本身的变化忽略
+        if event != WatchEvent::Deleted && path.is_dir() {
+            return false;
+        }
+        if self.any {
+            return true;
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        path.file_name()
+            .is_some_and(|name| self.names.is_match(name))
+            || self.paths.is_match(relative)
+    }
+
+    // directory 类型的参数传入所在目录
+    fn target(&self, path: &Path) -> PathBuf {
+        match path.parent() {
+            Some(parent) if self.directory_param => parent.to_path_buf(),
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+struct PendingChange {
+    first: WatchEvent,
+    last: WatchEvent,
+    due: Instant,
+}
+
+impl PendingChange {
+    // 窗口内创建后又删除的文件忽略
+    fn outcome(&self) -> Option<WatchEvent> {
+        match (self.first, self.last) {
+            (WatchEvent::Created, WatchEvent::Deleted) => None,
+            (_, WatchEvent::Deleted) => Some(WatchEvent::Deleted),
+            (WatchEvent::Created, _) => Some(WatchEvent::Created),
+            _ => Some(WatchEvent::Modified),
+        }
+    }
+}
+
+// 同一路径的变化在去抖窗口内没有新变化后合并，每个变化的路径启动一次执行
+#[derive(Clone)]
+pub struct FileWatcher {
+    watch_repo: FileWatchRepository,
+    executions: ExecutionService,
+    active: Arc<Mutex<HashMap<String, ActiveWatch>>>,
+}
+
+impl FileWatcher {
+    pub fn new(watch_repo: FileWatchRepository, executions: ExecutionService) -> Self {
+        Self {
+            watch_repo,
+            executions,
+            active: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn list_watches(&self) -> Result<Vec<FileWatch>> {
+        self.watch_repo.list().await
+    }
+
+    pub async fn get_watch(&self, id: &str) -> Result<FileWatch> {
+        self.watch_repo.get(id).await
+    }
+
+    pub async fn create_watch(&self, settings: FileWatchSettings) -> Result<FileWatch> {
+        let param_type = self.validate(&settings).await?;
+        let now = Utc::now().timestamp_millis();
+        let watch = FileWatch {
+            id: Uuid::new_v4().to_string(),
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            path: settings.path,
+            recursive: settings.recursive,
+            patterns: Self::serialize(&settings.patterns)?,
+            events: Self::serialize(&settings.events)?,
+            debounce_ms: settings.debounce_ms,
+            path_param: settings.path_param,
+            params: Self::serialize(&settings.params)?,
+            enabled: settings.enabled,
+            last_triggered_at: None,
+            last_execution_id: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.watch_repo.create(&watch).await?;
+        if watch.enabled {
+            self.start(&watch, param_type)?;
+        }
+        Ok(watch)
+    }
+
+    pub async fn update_watch(&self, id: &str, settings: FileWatchSettings) -> Result<FileWatch> {
+        let existing = self.watch_repo.get(id).await?;
+        let param_type = self.validate(&settings).await?;
+        let watch = FileWatch {
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            path: settings.path,
+            recursive: settings.recursive,
+            patterns: Self::serialize(&settings.patterns)?,
+            events: Self::serialize(&settings.events)?,
+            debounce_ms: settings.debounce_ms,
+            path_param: settings.path_param,
+            params: Self::serialize(&settings.params)?,
+            enabled: settings.enabled,
+            last_error: None,
+            updated_at: Utc::now().timestamp_millis(),
+            ..existing
+        };
+        self.watch_repo.update(&watch).await?;
+        self.stop(id);
+        if watch.enabled {
+            self.start(&watch, param_type)?;
+        }
+        Ok(watch)
+    }
+
+    pub async fn delete_watch(&self, id: &str) -> Result<()> {
+        self.watch_repo.delete(id).await?;
+        self.stop(id);
+        Ok(())
+    }
+
+    // 无法启动的监听（例如目录已删除）记录错误后跳过
+    pub async fn start_all(&self) -> Result<()> {
+        for watch in self.watch_repo.list().await? {
+            if !watch.enabled {
+                continue;
+            }
+            let started = match self
+                .path_param_type(&watch.plugin_id, &watch.path_param)
+                .await
+            {
+                Ok(param_type) => self.start(&watch, param_type),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = started {
+                tracing::warn!("Failed to start file watch {}: {}", watch.id, err);
+                self.watch_repo
+                    .record_error(&watch.id, &err.to_string())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&self, watch: &FileWatch, param_type: PluginParamType) -> Result<()> {
+        let filter = WatchFilter::new(watch, param_type)?;
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let watch_error =
+            |e: notify::Error| AppError::Watch(format!("Failed to watch '{}': {}", watch.path, e));
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = events_tx.send(event);
+        })
+        .map_err(watch_error)?;
+        let mode = if watch.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(Path::new(&watch.path), mode)
+            .map_err(watch_error)?;
+
+        let service = self.clone();
+        let running = watch.clone();
+        let task = tokio::spawn(async move { service.run(running, filter, events_rx).await });
+        // 替换旧条目时会停止之前的监视
+        self.active.lock().unwrap().insert(
+            watch.id.clone(),
+            ActiveWatch {
+                _watcher: watcher,
+                task,
+            },
+        );
+        Ok(())
+    }
+
+    fn stop(&self, id: &str) {
+        self.active.lock().unwrap().remove(id);
+    }
+
+    async fn run(
+        &self,
+        watch: FileWatch,
+        filter: WatchFilter,
+        mut events: mpsc::UnboundedReceiver<notify::Result<Event>>,
+    ) {
+        let debounce = Duration::from_millis(watch.debounce_ms as u64);
+        let mut pending: HashMap<PathBuf, PendingChange> = HashMap::new();
+        loop {
+            let next_due = pending.values().map(|change| change.due).min();
+            tokio::select! {
+                received = events.recv() => match received {
+                    None => return,
+                    Some(Ok(event)) => {
+                        let due = Instant::now() + debounce;
+                        for (path, kind) in Self::changes(event) {
+                            pending
+                                .entry(path)
+                                .and_modify(|change| {
+                                    change.last = kind;
+                                    change.due = due;
+                                })
+                                .or_insert(PendingChange {
+                                    first: kind,
+                                    last: kind,
+                                    due,
+                                });
+                        }
+                    }
+                    Some(Err(err)) => {
+                        tracing::warn!("File watch {} reported an error: {}", watch.id, err);
+                        if let Err(err) = self.watch_repo.record_error(&watch.id, &err.to_string()).await {
+                            tracing::error!("Failed to record error of file watch {}: {}", watch.id, err);
+                        }
+                    }
+                },
+                _ = sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                    let now = Instant::now();
+                    let ready: Vec<PathBuf> = pending
+                        .iter()
+                        .filter(|(_, change)| change.due <= now)
+                        .map(|(path, _)| path.clone())
+                        .collect();
+                    let mut targets = BTreeSet::new();
+                    for path in ready {
+                        let Some(change) = pending.remove(&path) else {
+                            continue;
+                        };
+                        if let Some(event) = change.outcome()
+                            && filter.accepts(&path, event)
+                        {
+                            targets.insert(filter.target(&path));
+                        }
+                    }
+                    for target in targets {
+                        self.trigger(&watch, &target).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn trigger(&self, watch: &FileWatch, target: &Path) {
+        let started = match serde_json::from_str::<HashMap<String, Value>>(&watch.params) {
+            Ok(mut params) => {
+                params.insert(
+                    watch.path_param.clone(),
+                    Value::String(target.to_string_lossy().to_string()),
+                );
+                self.executions
+                    .execute_triggered(&watch.plugin_id, params, format!("watch:{}", watch.id))
+                    .await
+            }
+            Err(e) => Err(AppError::Watch(format!("Invalid watch params: {}", e))),
+        };
+        let recorded = match started {
+            Ok(execution) => {
+                tracing::info!(
+                    "File watch {} started execution {} for {}",
+                    watch.id,
+                    execution.id,
+                    target.display()
+                );
+                self.watch_repo
+                    .record_trigger(&watch.id, Some(&execution.id), None)
+                    .await
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "File watch {} failed to start for {}: {}",
+                    watch.id,
+                    target.display(),
+                    err
+                );
+                self.watch_repo
+                    .record_trigger(&watch.id, None, Some(err.to_string()))
+                    .await
+            }
+        };
+        if let Err(err) = recorded {
+            tracing::error!(
+                "Failed to record trigger of file watch {}: {}",
+                watch.id,
+                err
+            );
+        }
+    }
+
+    fn changes(event: Event) -> Vec<(PathBuf, WatchEvent)> {
+        let kind = match event.kind {
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                WatchEvent::Created
+            }
+            EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                WatchEvent::Deleted
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let mut paths = event.paths.into_iter();
+                return match (paths.next(), paths.next()) {
+                    (Some(from), Some(to)) => {
+                        vec![(from, WatchEvent::Deleted), (to, WatchEvent::Created)]
+                    }
+                    _ => Vec::new(),
+                };
+            }
+            EventKind::Modify(ModifyKind::Metadata(_)) => return Vec::new(),
+            EventKind::Modify(_) => WatchEvent::Modified,
+            _ => return Vec::new(),
+        };
+        event.paths.into_iter().map(|path| (path, kind)).collect()
+    }
+
+    async fn validate(&self, settings: &FileWatchSettings) -> Result<PluginParamType> {
+        if settings.name.trim().is_empty() {
+            return Err(AppError::Watch(
+                "File watch name cannot be empty".to_string(),
+            ));
+        }
+        let path = Path::new(&settings.path);
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(AppError::Watch(format!(
+                "Watch path must be an existing absolute directory: {}",
+                settings.path
+            )));
+        }
+        if settings.events.is_empty() {
+            return Err(AppError::Watch("Select at least one event".to_string()));
+        }
+        if !(0..=MAX_DEBOUNCE_MS).contains(&settings.debounce_ms) {
+            return Err(AppError::Watch(format!(
+                "debounce_ms must be between 0 and {}",
+                MAX_DEBOUNCE_MS
+            )));
+        }
+        for pattern in &settings.patterns {
+            Glob::new(pattern).map_err(|e| {
+                AppError::Watch(format!("Invalid glob pattern '{}': {}", pattern, e))
+            })?;
+        }
+
+        let param_type = self
+            .path_param_type(&settings.plugin_id, &settings.path_param)
+            .await?;
+        if settings.params.contains_key(&settings.path_param) {
+            return Err(AppError::Watch(format!(
+                "Parameter '{}' is set by the watch and cannot be fixed",
+                settings.path_param
+            )));
+        }
+        let mut params = settings.params.clone();
+        params.insert(
+            settings.path_param.clone(),
+            Value::String(settings.path.clone()),
+        );
+        self.executions
+            .check_parameters(&settings.plugin_id, params)
+            .await?;
+        Ok(param_type)
+    }
+
+    async fn path_param_type(&self, plugin_id: &str, name: &str) -> Result<PluginParamType> {
+        let declared = self.executions.plugin_parameters(plugin_id).await?;
+        match declared.iter().find(|param| param.name == name) {
+            Some(param)
+                if matches!(
+                    param.param_type,
+                    PluginParamType::File | PluginParamType::Directory
+                ) =>
+            {
+                Ok(param.param_type.clone())
+            }
+            Some(_) => Err(AppError::Watch(format!(
+                "Parameter '{}' must be of type file or directory",
+                name
+            ))),
+            None => Err(AppError::Watch(format!(
+                "Plugin '{}' has no parameter '{}'",
+                plugin_id, name
+            ))),
+        }
+    }
+
+    fn serialize<T: serde::Serialize>(value: &T) -> Result<String> {
+        serde_json::to_string(value)
+            .map_err(|e| AppError::Watch(format!("Failed to serialize file watch: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, MetadataKind, RemoveKind};
+
+    fn outcome(first: WatchEvent, last: WatchEvent) -> Option<WatchEvent> {
+        PendingChange {
+            first,
+            last,
+            due: Instant::now(),
+        }
+        .outcome()
+    }
+
+    fn filter(patterns: &str, events: &str, param_type: PluginParamType) -> WatchFilter {
+        let watch = FileWatch {
+            id: "watch".to_string(),
+            name: "watch".to_string(),
+            plugin_id: "plugin".to_string(),
+            path: "/watched".to_string(),
+            recursive: true,
+            patterns: patterns.to_string(),
+            events: events.to_string(),
+            debounce_ms: 500,
+            path_param: "path".to_string(),
+            params: "{}".to_string(),
+            enabled: true,
+            last_triggered_at: None,
+            last_execution_id: None,
+            last_error: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        WatchFilter::new(&watch, param_type).unwrap()
+    }
+
+    #[test]
+    fn folds_changes_within_the_debounce_window() {
+        use WatchEvent::*;
+        assert_eq!(outcome(Created, Created), Some(Created));
+        assert_eq!(outcome(Created, Modified), Some(Created));
+        assert_eq!(outcome(Created, Deleted), None);
+        assert_eq!(outcome(Modified, Modified), Some(Modified));
+        assert_eq!(outcome(Modified, Deleted), Some(Deleted));
+        assert_eq!(outcome(Deleted, Created), Some(Modified));
+        assert_eq!(outcome(Deleted, Deleted), Some(Deleted));
+    }
+
+    #[test]
+    fn maps_notify_events() {
+        let path = PathBuf::from("/watched/a.txt");
+        let changes =
+            |kind: EventKind| FileWatcher::changes(Event::new(kind).add_path(path.clone()));
+        assert_eq!(
+            changes(EventKind::Create(CreateKind::File)),
+            vec![(path.clone(), WatchEvent::Created)]
+        );
+        assert_eq!(
+            changes(EventKind::Modify(ModifyKind::Data(DataChange::Content))),
+            vec![(path.clone(), WatchEvent::Modified)]
+        );
+        assert_eq!(
+            changes(EventKind::Remove(RemoveKind::File)),
+            vec![(path.clone(), WatchEvent::Deleted)]
+        );
+        assert!(changes(EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any))).is_empty());
+
+        let renamed = PathBuf::from("/watched/b.txt");
+        let event = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(path.clone())
+            .add_path(renamed.clone());
+        assert_eq!(
+            FileWatcher::changes(event),
+            vec![(path, WatchEvent::Deleted), (renamed, WatchEvent::Created)]
+        );
+    }
+
+    #[test]
+    fn matches_names_and_relative_paths() {
+        let filter = filter(
+            r#"["*.csv", "reports/**/*.pdf"]"#,
+            r#"["created", "modified"]"#,
+            PluginParamType::File,
+        );
+        let accepts = |path: &str, event| filter.accepts(Path::new(path), event);
+        assert!(accepts("/watched/in/data.csv", WatchEvent::Created));
+        assert!(accepts(
+            "/watched/reports/2024/q1.pdf",
+            WatchEvent::Modified
+        ));
+        assert!(!accepts("/watched/q1.pdf", WatchEvent::Created));
+        assert!(!accepts("/watched/data.csv", WatchEvent::Deleted));
+        assert_eq!(
+            filter.target(Path::new("/watched/in/data.csv")),
+            PathBuf::from("/watched/in/data.csv")
+        );
+    }
+
+    #[test]
+    fn empty_patterns_match_every_file() {
+        let filter = filter("[]", r#"["deleted"]"#, PluginParamType::Directory);
+        assert!(filter.accepts(Path::new("/watched/any/file"), WatchEvent::Deleted));
+        assert_eq!(
+            filter.target(Path::new("/watched/any/file")),
+            PathBuf::from("/watched/any")
+        );
+    }
+}
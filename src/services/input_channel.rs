@@ -0,0 +1,69 @@
+use crate::error::{AppError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::process::ChildStdin;
+use tokio::sync::{Mutex, watch};
+
+#[derive(Clone, Default)]
+pub struct InputChannels {
+    stdins: Arc<Mutex<HashMap<String, ChildStdin>>>,
+    // 执行是否正在等待输入，超时计时在等待期间暂停
+    waiting: Arc<std::sync::Mutex<HashMap<String, watch::Sender<bool>>>>,
+}
+
+impl InputChannels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn attach(&self, execution_id: &str, stdin: ChildStdin) {
+        self.stdins
+            .lock()
+            .await
+            .insert(execution_id.to_string(), stdin);
+    }
+
+    pub async fn detach(&self, execution_id: &str) {
+        self.stdins.lock().await.remove(execution_id);
+        self.waiting.lock().unwrap().remove(execution_id);
+    }
+
+    pub fn watch_waiting(&self, execution_id: &str) -> watch::Receiver<bool> {
+        let (tx, rx) = watch::channel(false);
+        self.waiting
+            .lock()
+            .unwrap()
+            .insert(execution_id.to_string(), tx);
+        rx
+    }
+
+    pub fn set_waiting(&self, execution_id: &str, waiting: bool) {
+        if let Some(tx) = self.waiting.lock().unwrap().get(execution_id) {
+            tx.send_replace(waiting);
+        }
+    }
+
+    pub async fn send(&self, execution_id: &str, line: &str) -> Result<()> {
+        let mut stdins = self.stdins.lock().await;
+        let stdin = stdins
+            .get_mut(execution_id)
+            .ok_or_else(|| AppError::Execution("Execution is not running".to_string()))?;
+
+        let mut payload = line.as_bytes().to_vec();
+        payload.push(b'\n');
+        let written = async {
+            stdin.write_all(&payload).await?;
+            stdin.flush().await
+        }
+        .await;
+        if let Err(err) = written {
+            stdins.remove(execution_id);
+            return Err(AppError::Execution(format!(
+                "Failed to send input to plugin: {}",
+                err
+            )));
+        }
+        Ok(())
+    }
+}
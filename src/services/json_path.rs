@@ -0,0 +1,122 @@
+// JSONPath 的一个子集：$、.name、['name']、["name"] 和 [index]，例如 $.commits[0].id
+
+use crate::error::{AppError, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            AppError::Webhook(format!("Invalid JSON path '{}': {}", expression, reason))
+        };
+        let Some(mut rest) = expression.trim().strip_prefix('$') else {
+            return Err(invalid("must start with '$'"));
+        };
+
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                if end == 0 {
+                    return Err(invalid("empty key"));
+                }
+                segments.push(Segment::Key(after[..end].to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(|| invalid("missing ']'"))?;
+                let inner = after[..end].trim();
+                let quoted = ['\'', '"'].iter().find_map(|quote| {
+                    inner
+                        .strip_prefix(*quote)
+                        .and_then(|key| key.strip_suffix(*quote))
+                });
+                let segment = match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None => Segment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("expected an index or a quoted key"))?,
+                    ),
+                };
+                segments.push(segment);
+                rest = &after[end + 1..];
+            } else {
+                return Err(invalid("expected '.' or '['"));
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                Segment::Key(key) => current.get(key.as_str()),
+                Segment::Index(index) => current.get(*index),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(name: &str) -> Segment {
+        Segment::Key(name.to_string())
+    }
+
+    #[test]
+    fn parses_keys_and_indexes() {
+        let path = JsonPath::parse(" $.commits[0]['author name'][\"id\"].x ").unwrap();
+        assert_eq!(
+            path.segments,
+            vec![
+                key("commits"),
+                Segment::Index(0),
+                key("author name"),
+                key("id"),
+                key("x")
+            ]
+        );
+        assert!(JsonPath::parse("$").unwrap().segments.is_empty());
+        assert_eq!(
+            JsonPath::parse("$['a.b']").unwrap().segments,
+            vec![key("a.b")]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        for expression in ["", "commits", "$.", "$..a", "$[0", "$[x]", "$['a]", "$a"] {
+            assert!(JsonPath::parse(expression).is_err(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn selects_values() {
+        let body = json!({
+            "ref": "refs/heads/main",
+            "commits": [{ "id": "c1" }, { "id": "c2" }],
+            "a.b": 1
+        });
+        let select = |expression: &str| JsonPath::parse(expression).unwrap().select(&body).cloned();
+        assert_eq!(select("$"), Some(body.clone()));
+        assert_eq!(select("$.ref"), Some(json!("refs/heads/main")));
+        assert_eq!(select("$.commits[1].id"), Some(json!("c2")));
+        assert_eq!(select("$['a.b']"), Some(json!(1)));
+        assert_eq!(select("$.commits[2]"), None);
+        assert_eq!(select("$.ref[0]"), None);
+        assert_eq!(select("$.missing.id"), None);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::error::Result;
+use crate::models::{LogLimits, LogStream};
+use crate::paths;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+pub const MAX_READ_BYTES: usize = 1024 * 1024;
+const DEFAULT_READ_BYTES: usize = 64 * 1024;
+
+// 只保留开头和结尾，总长度不超过 limit
+pub struct TruncatedCapture {
+    limit: Option<usize>,
+    head: String,
+    tail: String,
+    total_bytes: usize,
+}
+
+impl TruncatedCapture {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            head: String::new(),
+            tail: String::new(),
+            total_bytes: 0,
+        }
+    }
+
+    pub fn push(&mut self, text: &str) {
+        self.total_bytes += text.len();
+        let Some(limit) = self.limit else {
+            self.head.push_str(text);
+            return;
+        };
+
+        let head_limit = limit / 2;
+        let mut rest = text;
+        if self.head.len() < head_limit {
+            let split = floor_char_boundary(rest, head_limit - self.head.len());
+            self.head.push_str(&rest[..split]);
+            rest = &rest[split..];
+        }
+        self.tail.push_str(rest);
+
+        // 尾部允许暂时超出，攒够一倍再裁剪，避免每行都搬移整个缓冲区
+        let tail_limit = limit - head_limit;
+        if self.tail.len() > tail_limit * 2 {
+            let cut = ceil_char_boundary(&self.tail, self.tail.len() - tail_limit);
+            self.tail.drain(..cut);
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        if let Some(limit) = self.limit {
+            let tail_limit = limit - limit / 2;
+            if self.tail.len() > tail_limit {
+                let cut = ceil_char_boundary(&self.tail, self.tail.len() - tail_limit);
+                self.tail.drain(..cut);
+            }
+        }
+
+        let dropped = self.total_bytes - self.head.len() - self.tail.len();
+        if dropped == 0 {
+            return self.head + &self.tail;
+        }
+        if !self.head.ends_with('\n') {
+            self.head.push('\n');
+        }
+        format!(
+            "{}... [{} bytes truncated, full log available from the logs endpoint] ...\n{}",
+            self.head, dropped, self.tail
+        )
+    }
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+// 日志文件位于 data/logs/<execution_id>，以首字节在流中的偏移命名，例如 stdout.8388608.log
+pub struct LogWriter {
+    dir: PathBuf,
+    stream: LogStream,
+    limits: LogLimits,
+    segment_start: u64,
+    written: u64,
+    file: Option<tokio::fs::File>,
+}
+
+impl LogWriter {
+    // 接在已写入的输出之后，例如预览之后的 apply 阶段
+    pub async fn open(execution_id: &str, stream: LogStream, limits: LogLimits) -> Result<Self> {
+        let dir = log_dir(execution_id)?;
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let (segment_start, written) = match segments(&dir, stream)?.last() {
+            Some((start, path)) => (*start, tokio::fs::metadata(path).await?.len()),
+            None => (0, 0),
+        };
+        Ok(Self {
+            dir,
+            stream,
+            limits,
+            segment_start,
+            written,
+            file: None,
+        })
+    }
+
+    pub async fn write(&mut self, mut bytes: &[u8]) -> Result<()> {
+        while !bytes.is_empty() {
+            let room = match self.limits.segment_bytes {
+                Some(limit) if self.written >= limit => {
+                    self.rotate().await?;
+                    limit
+                }
+                Some(limit) => limit - self.written,
+                None => u64::MAX,
+            };
+            let chunk = bytes.len().min(usize::try_from(room).unwrap_or(usize::MAX));
+
+            if self.file.is_none() {
+                let path = segment_path(&self.dir, self.stream, self.segment_start);
+                let file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?;
+                self.file = Some(file);
+            }
+            if let Some(file) = self.file.as_mut() {
+                file.write_all(&bytes[..chunk]).await?;
+            }
+            self.written += chunk as u64;
+            bytes = &bytes[chunk..];
+        }
+        Ok(())
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.file = None;
+        self.segment_start += self.written;
+        self.written = 0;
+
+        if let Some(max_segments) = self.limits.max_segments {
+            let existing = segments(&self.dir, self.stream)?;
+            // 新分段尚未创建，保留 max_segments - 1 个旧分段
+            let excess = (existing.len() + 1).saturating_sub(max_segments);
+            for (_, path) in existing.into_iter().take(excess) {
+                tokio::fs::remove_file(path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogChunk {
+    pub stream: LogStream,
+    pub offset: u64,
+    pub next_offset: u64,
+    // 轮转会删除更早的输出
+    pub start_offset: u64,
+    pub size: u64,
+    pub data: String,
+}
+
+pub async fn read(
+    execution_id: &str,
+    stream: LogStream,
+    offset: u64,
+    limit: Option<usize>,
+) -> Result<Option<LogChunk>> {
+    let dir = log_dir(execution_id)?;
+    let segments = segments(&dir, stream)?;
+    let (Some((start_offset, _)), Some((last_start, last_path))) =
+        (segments.first(), segments.last())
+    else {
+        return Ok(None);
+    };
+    let start_offset = *start_offset;
+    let size = last_start + tokio::fs::metadata(last_path).await?.len();
+
+    let offset = offset.clamp(start_offset, size);
+    let limit = limit.unwrap_or(DEFAULT_READ_BYTES).clamp(1, MAX_READ_BYTES);
+    let mut data = Vec::new();
+    let mut position = offset;
+    for (index, (segment_start, path)) in segments.iter().enumerate() {
+        let segment_end = segments
+            .get(index + 1)
+            .map(|(next_start, _)| *next_start)
+            .unwrap_or(size);
+        if position >= segment_end || data.len() >= limit {
+            continue;
+        }
+
+        let mut file = tokio::fs::File::open(path).await?;
+        file.seek(std::io::SeekFrom::Start(position - segment_start))
+            .await?;
+        let wanted = (limit - data.len()) as u64;
+        let read = file.take(wanted).read_to_end(&mut data).await?;
+        position += read as u64;
+    }
+
+    Ok(Some(LogChunk {
+        stream,
+        offset,
+        next_offset: position,
+        start_offset,
+        size,
+        data: String::from_utf8_lossy(&data).into_owned(),
+    }))
+}
+
+// 没有日志文件的执行只能读取数据库中保存的输出
+pub fn read_stored(stream: LogStream, stored: &str, offset: u64, limit: Option<usize>) -> LogChunk {
+    let size = stored.len() as u64;
+    let offset = offset.min(size);
+    let limit = limit.unwrap_or(DEFAULT_READ_BYTES).clamp(1, MAX_READ_BYTES) as u64;
+    let end = (offset + limit).min(size);
+    LogChunk {
+        stream,
+        offset,
+        next_offset: end,
+        start_offset: 0,
+        size,
+        data: String::from_utf8_lossy(&stored.as_bytes()[offset as usize..end as usize])
+            .into_owned(),
+    }
+}
+
+pub fn remove(execution_id: &str) -> Result<()> {
+    crate::services::artifact_store::remove_dir_if_exists(&log_dir(execution_id)?)
+}
+
+fn log_dir(execution_id: &str) -> Result<PathBuf> {
+    Ok(paths::logs_dir()?.join(execution_id))
+}
+
+fn segment_path(dir: &Path, stream: LogStream, start: u64) -> PathBuf {
+    dir.join(format!("{}.{}.log", stream.as_str(), start))
+}
+
+fn segments(dir: &Path, stream: LogStream) -> Result<Vec<(u64, PathBuf)>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let prefix = format!("{}.", stream.as_str());
+    let mut segments = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let start = name
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(".log"))
+            .and_then(|start| start.parse::<u64>().ok());
+        if let Some(start) = start {
+            segments.push((start, entry.path()));
+        }
+    }
+    segments.sort_by_key(|(start, _)| *start);
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(limit: Option<usize>, pushes: &[&str]) -> String {
+        let mut capture = TruncatedCapture::new(limit);
+        for text in pushes {
+            capture.push(text);
+        }
+        capture.finish()
+    }
+
+    fn writer(dir: &Path, segment_bytes: Option<u64>, max_segments: Option<usize>) -> LogWriter {
+        LogWriter {
+            dir: dir.to_path_buf(),
+            stream: LogStream::Stdout,
+            limits: LogLimits {
+                max_db_bytes: None,
+                segment_bytes,
+                max_segments,
+            },
+            segment_start: 0,
+            written: 0,
+            file: None,
+        }
+    }
+
+    fn segment_files(dir: &Path) -> Vec<(u64, String)> {
+        segments(dir, LogStream::Stdout)
+            .unwrap()
+            .into_iter()
+            .map(|(start, path)| (start, std::fs::read_to_string(path).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_short_output_unchanged() {
+        assert_eq!(capture(None, &["a\n", "b\n"]), "a\nb\n");
+        assert_eq!(capture(Some(10), &["abc\n", "def\n"]), "abc\ndef\n");
+    }
+
+    #[test]
+    fn keeps_head_and_tail_of_long_output() {
+        let lines: Vec<String> = (0..100).map(|line| format!("{:02}\n", line)).collect();
+        let pushes: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = capture(Some(12), &pushes);
+        assert_eq!(
+            output,
+            "00\n01\n... [288 bytes truncated, full log available from the logs endpoint] ...\n98\n99\n"
+        );
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        let output = capture(Some(8), &["日志日志日志"]);
+        assert!(
+            output.starts_with("日\n... [12 bytes truncated"),
+            "{}",
+            output
+        );
+        assert!(output.ends_with("] ...\n志"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn rotates_segments_and_drops_old_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = writer(dir.path(), Some(4), Some(2));
+        log.write(b"abcdef").await.unwrap();
+        log.write(b"ghij").await.unwrap();
+        log.flush().await.unwrap();
+        assert_eq!(
+            segment_files(dir.path()),
+            vec![(4, "efgh".to_string()), (8, "ij".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_one_file_without_segment_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = writer(dir.path(), None, Some(1));
+        log.write(b"abc").await.unwrap();
+        log.write(b"def").await.unwrap();
+        log.flush().await.unwrap();
+        assert_eq!(segment_files(dir.path()), vec![(0, "abcdef".to_string())]);
+    }
+
+    #[test]
+    fn reads_stored_output_by_range() {
+        let chunk = read_stored(LogStream::Stderr, "hello world", 6, Some(3));
+        assert_eq!(chunk.data, "wor");
+        assert_eq!((chunk.offset, chunk.next_offset, chunk.size), (6, 9, 11));
+        let chunk = read_stored(LogStream::Stderr, "hello", 10, None);
+        assert_eq!(
+            (chunk.offset, chunk.next_offset, chunk.data.as_str()),
+            (5, 5, "")
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::models::{ExecutionProgress, ExecutionStatus, LogStream};
+use crate::services::plugin_protocol::InputRequest;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+const LOG_CHANNEL_CAPACITY: usize = 1024;
+const LOG_HISTORY_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub stream: LogStream,
+    pub line: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    Log(LogLine),
+    Progress(ExecutionProgress),
+    InputRequest(InputRequest),
+    End {
+        status: ExecutionStatus,
+        exit_code: Option<i32>,
+    },
+}
+
+impl LogEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Log(_) => "log",
+            Self::Progress(_) => "progress",
+            Self::InputRequest(_) => "input_request",
+            Self::End { .. } => "end",
+        }
+    }
+}
+
+pub struct LogSubscription {
+    pub history: Vec<LogLine>,
+    pub progress: Option<ExecutionProgress>,
+    pub receiver: broadcast::Receiver<LogEvent>,
+}
+
+struct ExecutionLog {
+    history: VecDeque<LogLine>,
+    progress: Option<ExecutionProgress>,
+    sender: broadcast::Sender<LogEvent>,
+}
+
+#[derive(Clone, Default)]
+pub struct LogHub {
+    logs: Arc<Mutex<HashMap<String, ExecutionLog>>>,
+}
+
+impl LogHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&self, execution_id: &str) {
+        let (sender, _) = broadcast::channel(LOG_CHANNEL_CAPACITY);
+        self.logs.lock().unwrap().insert(
+            execution_id.to_string(),
+            ExecutionLog {
+                history: VecDeque::new(),
+                progress: None,
+                sender,
+            },
+        );
+    }
+
+    pub fn push(&self, execution_id: &str, stream: LogStream, line: String) {
+        let mut logs = self.logs.lock().unwrap();
+        let Some(log) = logs.get_mut(execution_id) else {
+            return;
+        };
+        let line = LogLine {
+            stream,
+            line,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        if log.history.len() >= LOG_HISTORY_LIMIT {
+            log.history.pop_front();
+        }
+        log.history.push_back(line.clone());
+        // 没有订阅者时发送失败可以忽略，后来的订阅者会回放历史
+        let _ = log.sender.send(LogEvent::Log(line));
+    }
+
+    pub fn push_progress(&self, execution_id: &str, progress: ExecutionProgress) {
+        let mut logs = self.logs.lock().unwrap();
+        let Some(log) = logs.get_mut(execution_id) else {
+            return;
+        };
+        log.progress = Some(progress.clone());
+        let _ = log.sender.send(LogEvent::Progress(progress));
+    }
+
+    // 只发送给当前订阅者，不会重放
+    pub fn notify(&self, execution_id: &str, event: LogEvent) {
+        if let Some(log) = self.logs.lock().unwrap().get(execution_id) {
+            let _ = log.sender.send(event);
+        }
+    }
+
+    pub fn subscribe(&self, execution_id: &str) -> Option<LogSubscription> {
+        let logs = self.logs.lock().unwrap();
+        let log = logs.get(execution_id)?;
+        Some(LogSubscription {
+            history: log.history.iter().cloned().collect(),
+            progress: log.progress.clone(),
+            receiver: log.sender.subscribe(),
+        })
+    }
+
+    // 关闭通道会结束所有订阅
+    pub fn close(&self, execution_id: &str) {
+        self.logs.lock().unwrap().remove(execution_id);
+    }
+}
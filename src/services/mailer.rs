@@ -0,0 +1,522 @@
+// 模板字段：plugin_name、plugin_id、execution_id、status、exit_code、duration、error_message、triggered_by、stderr_tail、link
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    EmailConfig, EmailOutcome, EmailSubscription, Execution, ExecutionStatus, LogStream,
+};
+use crate::repository::{EmailSubscriptionRepository, PluginRepository};
+use crate::services::execution_service::ExecutionService;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use uuid::Uuid;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+const STDERR_TAIL_LINES: usize = 20;
+const STDERR_TAIL_BYTES: u64 = 8 * 1024;
+const FINISHED_STATUSES: [ExecutionStatus; 5] = [
+    ExecutionStatus::Completed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Stopped,
+    ExecutionStatus::TimedOut,
+    ExecutionStatus::Expired,
+];
+
+const TEMPLATE_FIELDS: [&str; 10] = [
+    "plugin_name",
+    "plugin_id",
+    "execution_id",
+    "status",
+    "exit_code",
+    "duration",
+    "error_message",
+    "triggered_by",
+    "stderr_tail",
+    "link",
+];
+const DEFAULT_SUBJECT_TEMPLATE: &str = "[AntHill] {{ plugin_name }}: {{ status }}";
+const DEFAULT_BODY_TEMPLATE: &str = "\
+Plugin:       {{ plugin_name }} ({{ plugin_id }})
+Execution:    {{ execution_id }}
+Status:       {{ status }}
+Exit code:    {{ exit_code }}
+Duration:     {{ duration }}
+Triggered by: {{ triggered_by }}
+Error:        {{ error_message }}
+
+Last lines of stderr:
+{{ stderr_tail }}
+
+{{ link }}
+";
+
+#[derive(Debug, Clone)]
+pub struct EmailSubscriptionSettings {
+    pub email: String,
+    pub plugin_id: Option<String>,
+    pub outcome: EmailOutcome,
+    pub enabled: bool,
+}
+
+struct Smtp {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    subject_template: String,
+    body_template: String,
+}
+
+// 没有配置 SMTP 时仍可管理订阅，但不会发送邮件
+#[derive(Clone)]
+pub struct Mailer {
+    subscription_repo: EmailSubscriptionRepository,
+    plugin_repo: PluginRepository,
+    executions: ExecutionService,
+    smtp: Option<Arc<Smtp>>,
+    public_url: String,
+}
+
+impl Mailer {
+    pub fn new(
+        subscription_repo: EmailSubscriptionRepository,
+        plugin_repo: PluginRepository,
+        executions: ExecutionService,
+        config: Option<EmailConfig>,
+        public_url: String,
+    ) -> Result<Self> {
+        let smtp = config.map(Self::connect).transpose()?.map(Arc::new);
+        Ok(Self {
+            subscription_repo,
+            plugin_repo,
+            executions,
+            smtp,
+            public_url: public_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn connect(config: EmailConfig) -> Result<Smtp> {
+        let builder = if config.smtp_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host).map_err(
+                |e| AppError::Email(format!("Invalid SMTP host '{}': {}", config.smtp_host, e)),
+            )?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        };
+        let mut builder = builder.port(config.smtp_port).timeout(Some(SEND_TIMEOUT));
+        if let Some(username) = config.smtp_username {
+            builder = builder.credentials(Credentials::new(
+                username,
+                config.smtp_password.unwrap_or_default(),
+            ));
+        }
+
+        let from = config
+            .from
+            .parse()
+            .map_err(|e| AppError::Email(format!("Invalid sender '{}': {}", config.from, e)))?;
+        let subject_template = config
+            .subject_template
+            .unwrap_or_else(|| DEFAULT_SUBJECT_TEMPLATE.to_string());
+        let body_template = config
+            .body_template
+            .unwrap_or_else(|| DEFAULT_BODY_TEMPLATE.to_string());
+        // 启动时校验模板，避免发送时才发现错误
+        let sample = HashMap::new();
+        render(&subject_template, &sample)?;
+        render(&body_template, &sample)?;
+
+        Ok(Smtp {
+            transport: builder.build(),
+            from,
+            subject_template,
+            body_template,
+        })
+    }
+
+    pub async fn list_subscriptions(&self) -> Result<Vec<EmailSubscription>> {
+        self.subscription_repo.list().await
+    }
+
+    pub async fn get_subscription(&self, id: &str) -> Result<EmailSubscription> {
+        self.subscription_repo.get(id).await
+    }
+
+    pub async fn create_subscription(
+        &self,
+        settings: EmailSubscriptionSettings,
+    ) -> Result<EmailSubscription> {
+        self.validate(&settings).await?;
+        let now = Utc::now().timestamp_millis();
+        let subscription = EmailSubscription {
+            id: Uuid::new_v4().to_string(),
+            email: settings.email.trim().to_string(),
+            plugin_id: settings.plugin_id,
+            outcome: settings.outcome,
+            enabled: settings.enabled,
+            last_sent_at: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.subscription_repo.create(&subscription).await?;
+        Ok(subscription)
+    }
+
+    pub async fn update_subscription(
+        &self,
+        id: &str,
+        settings: EmailSubscriptionSettings,
+    ) -> Result<EmailSubscription> {
+        let existing = self.subscription_repo.get(id).await?;
+        self.validate(&settings).await?;
+        let subscription = EmailSubscription {
+            email: settings.email.trim().to_string(),
+            plugin_id: settings.plugin_id,
+            outcome: settings.outcome,
+            enabled: settings.enabled,
+            updated_at: Utc::now().timestamp_millis(),
+            ..existing
+        };
+        self.subscription_repo.update(&subscription).await?;
+        Ok(subscription)
+    }
+
+    pub async fn delete_subscription(&self, id: &str) -> Result<()> {
+        self.subscription_repo.delete(id).await
+    }
+
+    pub async fn send_test(&self, id: &str) -> Result<()> {
+        let subscription = self.subscription_repo.get(id).await?;
+        let smtp = self.smtp()?;
+        let result = self
+            .send(
+                smtp,
+                &subscription.email,
+                "[AntHill] Test message".to_string(),
+                "Email notifications from AntHill reach this address.\n".to_string(),
+            )
+            .await;
+        self.subscription_repo
+            .record_send(&subscription.id, result.clone().err())
+            .await?;
+        result.map_err(AppError::Email)
+    }
+
+    pub fn spawn(&self) {
+        if self.smtp.is_none() {
+            tracing::info!("SMTP is not configured, email notifications are disabled");
+            return;
+        }
+        let service = self.clone();
+        let mut changes = self.executions.subscribe_statuses();
+        tokio::spawn(async move {
+            service.catch_up().await;
+            loop {
+                match changes.recv().await {
+                    Ok(change) if EmailOutcome::All.matches(change.status) => {
+                        let service = service.clone();
+                        // 发送邮件可能较慢，不阻塞状态接收
+                        tokio::spawn(async move {
+                            if let Err(err) =
+                                service.notify(&change.execution_id, change.status).await
+                            {
+                                tracing::error!(
+                                    "Failed to mail about execution {}: {}",
+                                    change.execution_id,
+                                    err
+                                );
+                            }
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Email notifications missed {} status changes", skipped);
+                        service.catch_up().await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+
+    // 状态广播会丢弃消息，启动时和接收落后时按数据库补发遗漏的邮件
+    async fn catch_up(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = service.send_missed().await {
+                tracing::error!("Failed to mail about missed executions: {}", err);
+            }
+        });
+    }
+
+    async fn send_missed(&self) -> Result<()> {
+        for subscription in self.subscription_repo.list().await? {
+            if !subscription.enabled {
+                continue;
+            }
+            let statuses: Vec<ExecutionStatus> = FINISHED_STATUSES
+                .into_iter()
+                .filter(|status| subscription.outcome.matches(*status))
+                .collect();
+            let missed = self
+                .subscription_repo
+                .list_missed(&subscription, &statuses, subscription.updated_at)
+                .await?;
+            for (execution_id, status) in missed {
+                match self.notify(&execution_id, status).await {
+                    Ok(()) | Err(AppError::ExecutionNotFound(_)) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn notify(&self, execution_id: &str, status: ExecutionStatus) -> Result<()> {
+        let execution = self.executions.get_execution(execution_id).await?;
+        let mut subscriptions: Vec<EmailSubscription> = Vec::new();
+        for subscription in self
+            .subscription_repo
+            .list_for_plugin(&execution.plugin_id)
+            .await?
+        {
+            // 同一执行的结束状态可能被发布多次，先登记再发送保证只发一次
+            if subscription.outcome.matches(status)
+                && self
+                    .subscription_repo
+                    .claim_send(&subscription.id, &execution.id)
+                    .await?
+            {
+                subscriptions.push(subscription);
+            }
+        }
+        if subscriptions.is_empty() {
+            return Ok(());
+        }
+
+        let smtp = self.smtp()?;
+        let fields = self.fields(&execution, status).await?;
+        let subject = render(&smtp.subject_template, &fields)?;
+        let body = render(&smtp.body_template, &fields)?;
+        for subscription in subscriptions {
+            let error = self
+                .send(smtp, &subscription.email, subject.clone(), body.clone())
+                .await
+                .err();
+            if let Some(error) = &error {
+                tracing::warn!(
+                    "Failed to mail {} about execution {}: {}",
+                    subscription.email,
+                    execution.id,
+                    error
+                );
+            }
+            self.subscription_repo
+                .record_send(&subscription.id, error)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn fields(
+        &self,
+        execution: &Execution,
+        status: ExecutionStatus,
+    ) -> Result<HashMap<&'static str, String>> {
+        let plugin_name = match self.plugin_repo.get(&execution.plugin_id).await {
+            Ok(plugin) => plugin.name,
+            Err(AppError::PluginNotFound(_)) => execution.plugin_id.clone(),
+            Err(err) => return Err(err),
+        };
+        let duration = execution
+            .finished_at
+            .map(|end| format_duration(end - execution.started_at))
+            .unwrap_or_else(|| "-".to_string());
+        let stderr_tail = self.stderr_tail(&execution.id).await?;
+
+        Ok(HashMap::from([
+            ("plugin_name", plugin_name),
+            ("plugin_id", execution.plugin_id.clone()),
+            ("execution_id", execution.id.clone()),
+            ("status", format!("{:?}", status)),
+            (
+                "exit_code",
+                execution
+                    .exit_code
+                    .map(|code| code.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            ("duration", duration),
+            (
+                "error_message",
+                execution
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            (
+                "triggered_by",
+                execution
+                    .triggered_by
+                    .clone()
+                    .unwrap_or_else(|| "manual".to_string()),
+            ),
+            ("stderr_tail", stderr_tail),
+            (
+                "link",
+                format!("{}/api/executions/{}", self.public_url, execution.id),
+            ),
+        ]))
+    }
+
+    async fn stderr_tail(&self, execution_id: &str) -> Result<String> {
+        let end = self
+            .executions
+            .read_logs(execution_id, LogStream::Stderr, u64::MAX, Some(1))
+            .await?
+            .size;
+        let chunk = self
+            .executions
+            .read_logs(
+                execution_id,
+                LogStream::Stderr,
+                end.saturating_sub(STDERR_TAIL_BYTES),
+                Some(STDERR_TAIL_BYTES as usize),
+            )
+            .await?;
+        let lines: Vec<&str> = chunk.data.trim_end().lines().collect();
+        if lines.is_empty() {
+            return Ok("(empty)".to_string());
+        }
+        Ok(lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n"))
+    }
+
+    async fn send(
+        &self,
+        smtp: &Smtp,
+        to: &str,
+        subject: String,
+        body: String,
+    ) -> std::result::Result<(), String> {
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| format!("Invalid recipient '{}': {}", to, e))?;
+        let message = Message::builder()
+            .from(smtp.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| format!("Failed to build message: {}", e))?;
+        smtp.transport
+            .send(message)
+            .await
+            .map_err(|e| format!("Failed to send message: {}", e))?;
+        Ok(())
+    }
+
+    fn smtp(&self) -> Result<&Smtp> {
+        self.smtp
+            .as_deref()
+            .ok_or_else(|| AppError::Email("SMTP is not configured".to_string()))
+    }
+
+    async fn validate(&self, settings: &EmailSubscriptionSettings) -> Result<()> {
+        settings.email.trim().parse::<Mailbox>().map_err(|e| {
+            AppError::Email(format!("Invalid email address '{}': {}", settings.email, e))
+        })?;
+        if let Some(plugin_id) = &settings.plugin_id {
+            self.plugin_repo.get(plugin_id).await?;
+        }
+        Ok(())
+    }
+}
+
+// fields 中没有的字段渲染为空，TEMPLATE_FIELDS 以外的名称报错
+fn render(template: &str, fields: &HashMap<&str, String>) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| AppError::Email(format!("Unclosed '{{{{' in template: {}", template)))?;
+        let name = after[..end].trim();
+        if !TEMPLATE_FIELDS.contains(&name) {
+            return Err(AppError::Email(format!(
+                "Unknown template field '{}', expected one of: {}",
+                name,
+                TEMPLATE_FIELDS.join(", ")
+            )));
+        }
+        output.push_str(fields.get(name).map(String::as_str).unwrap_or_default());
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn format_duration(ms: i64) -> String {
+    let ms = ms.max(0);
+    if ms < 60_000 {
+        return format!("{:.1}s", ms as f64 / 1000.0);
+    }
+    let seconds = ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_fields() {
+        let fields = HashMap::from([
+            ("plugin_name", "Backup".to_string()),
+            ("status", "Failed".to_string()),
+        ]);
+        assert_eq!(
+            render("[AntHill] {{plugin_name}}: {{ status }}", &fields).unwrap(),
+            "[AntHill] Backup: Failed"
+        );
+        assert_eq!(
+            render("error: {{ error_message }}.", &fields).unwrap(),
+            "error: ."
+        );
+        assert_eq!(render("no fields", &fields).unwrap(), "no fields");
+    }
+
+    #[test]
+    fn rejects_unknown_or_unclosed_fields() {
+        let fields = HashMap::new();
+        assert!(render("{{ password }}", &fields).is_err());
+        assert!(render("{{ status ", &fields).is_err());
+    }
+
+    #[test]
+    fn default_templates_only_use_known_fields() {
+        let fields = HashMap::new();
+        assert!(render(DEFAULT_SUBJECT_TEMPLATE, &fields).is_ok());
+        assert!(render(DEFAULT_BODY_TEMPLATE, &fields).is_ok());
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(-5), "0.0s");
+        assert_eq!(format_duration(1_250), "1.2s");
+        assert_eq!(format_duration(59_999), "60.0s");
+        assert_eq!(format_duration(61_000), "1m 01s");
+        assert_eq!(format_duration(3_723_000), "1h 02m 03s");
+    }
+}
@@ -1,7 +1,32 @@
+pub mod artifact_store;
+pub mod execution_queue;
 pub mod execution_service;
+pub mod file_watcher;
+pub mod input_channel;
+pub mod json_path;
+pub mod log_store;
+pub mod log_stream;
+pub mod mailer;
+pub mod notifier;
+pub mod plugin_protocol;
 pub mod plugin_service;
+pub mod process_registry;
+pub mod retention;
+pub mod runtime_service;
+pub mod scheduler;
+pub mod status_watch;
 pub mod update_service;
+pub mod webhook_service;
+pub mod workflow_service;
+pub mod workflow_template;
 
 pub use execution_service::ExecutionService;
+pub use file_watcher::FileWatcher;
+pub use mailer::Mailer;
+pub use notifier::Notifier;
 pub use plugin_service::PluginService;
+pub use runtime_service::RuntimeService;
+pub use scheduler::SchedulerService;
 pub use update_service::UpdateService;
+pub use webhook_service::WebhookService;
+pub use workflow_service::WorkflowService;
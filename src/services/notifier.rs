@@ -0,0 +1,478 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    DeliveryStatus, Execution, ExecutionStatus, NotificationDelivery, NotificationTarget,
+};
+use crate::repository::notification_repository::DeliveryFilter;
+use crate::repository::{NotificationRepository, PluginRepository};
+use crate::services::execution_service::ExecutionService;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::sync::{Notify, broadcast};
+use tokio::time::{Duration, sleep};
+use uuid::Uuid;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: i64 = 6;
+const RETRY_BASE_MS: i64 = 10_000;
+const RETRY_MAX_MS: i64 = 3_600_000;
+// 投递中的记录在租约到期前不会被再次领取
+const LEASE_MS: i64 = 60_000;
+const MAX_SLEEP_MS: i64 = 60_000;
+
+// secret 为 None 时更新保留原值，空字符串表示删除
+#[derive(Debug, Clone)]
+pub struct NotificationTargetSettings {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    pub plugin_id: Option<String>,
+    pub events: Vec<ExecutionStatus>,
+    pub enabled: bool,
+}
+
+// 每次投递先入库，按指数退避重试直到成功或用完次数
+#[derive(Clone)]
+pub struct Notifier {
+    notification_repo: NotificationRepository,
+    plugin_repo: PluginRepository,
+    executions: ExecutionService,
+    client: reqwest::Client,
+    public_url: String,
+    wake: Arc<Notify>,
+}
+
+impl Notifier {
+    pub fn new(
+        notification_repo: NotificationRepository,
+        plugin_repo: PluginRepository,
+        executions: ExecutionService,
+        public_url: String,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            notification_repo,
+            plugin_repo,
+            executions,
+            client,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn list_targets(&self) -> Result<Vec<NotificationTarget>> {
+        self.notification_repo.list_targets().await
+    }
+
+    pub async fn get_target(&self, id: &str) -> Result<NotificationTarget> {
+        self.notification_repo.get_target(id).await
+    }
+
+    pub async fn create_target(
+        &self,
+        settings: NotificationTargetSettings,
+    ) -> Result<NotificationTarget> {
+        self.validate(&settings).await?;
+        let now = Utc::now().timestamp_millis();
+        let target = NotificationTarget {
+            id: Uuid::new_v4().to_string(),
+            name: settings.name,
+            url: settings.url,
+            secret: settings.secret.filter(|secret| !secret.is_empty()),
+            plugin_id: settings.plugin_id,
+            events: Self::serialize_events(&settings.events)?,
+            enabled: settings.enabled,
+            created_at: now,
+            updated_at: now,
+        };
+        self.notification_repo.create_target(&target).await?;
+        Ok(target)
+    }
+
+    pub async fn update_target(
+        &self,
+        id: &str,
+        settings: NotificationTargetSettings,
+    ) -> Result<NotificationTarget> {
+        let existing = self.notification_repo.get_target(id).await?;
+        self.validate(&settings).await?;
+        let secret = match settings.secret {
+            Some(secret) => Some(secret).filter(|secret| !secret.is_empty()),
+            None => existing.secret.clone(),
+        };
+        let target = NotificationTarget {
+            name: settings.name,
+            url: settings.url,
+            secret,
+            plugin_id: settings.plugin_id,
+            events: Self::serialize_events(&settings.events)?,
+            enabled: settings.enabled,
+            updated_at: Utc::now().timestamp_millis(),
+            ..existing
+        };
+        self.notification_repo.update_target(&target).await?;
+        Ok(target)
+    }
+
+    pub async fn delete_target(&self, id: &str) -> Result<()> {
+        self.notification_repo.delete_target(id).await
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        filter: &DeliveryFilter,
+    ) -> Result<Vec<NotificationDelivery>> {
+        self.notification_repo.list_deliveries(filter).await
+    }
+
+    pub async fn get_delivery(&self, id: &str) -> Result<NotificationDelivery> {
+        self.notification_repo.get_delivery(id).await
+    }
+
+    pub async fn retry_delivery(&self, id: &str) -> Result<NotificationDelivery> {
+        if !self.notification_repo.retry_delivery(id).await? {
+            self.notification_repo.get_delivery(id).await?;
+            return Err(AppError::Notification(
+                "Only failed deliveries can be retried".to_string(),
+            ));
+        }
+        self.wake.notify_one();
+        self.notification_repo.get_delivery(id).await
+    }
+
+    pub fn spawn(&self) {
+        let service = self.clone();
+        let mut changes = self.executions.subscribe_statuses();
+        tokio::spawn(async move {
+            service.catch_up().await;
+            loop {
+                match changes.recv().await {
+                    Ok(change) => {
+                        if let Err(err) = service
+                            .queue_deliveries(&change.execution_id, change.status)
+                            .await
+                        {
+                            tracing::error!(
+                                "Failed to queue notifications for execution {}: {}",
+                                change.execution_id,
+                                err
+                            );
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Notifications missed {} execution status changes", skipped);
+                        service.catch_up().await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = service.send_due().await {
+                    tracing::error!("Failed to send notifications: {}", err);
+                }
+                let wait_ms = match service.notification_repo.next_attempt_at().await {
+                    Ok(Some(next)) => (next - Utc::now().timestamp_millis()).clamp(0, MAX_SLEEP_MS),
+                    Ok(None) => MAX_SLEEP_MS,
+                    Err(err) => {
+                        tracing::error!("Failed to read next notification attempt: {}", err);
+                        MAX_SLEEP_MS
+                    }
+                };
+                tokio::select! {
+                    _ = sleep(Duration::from_millis(wait_ms as u64)) => {}
+                    _ = service.wake.notified() => {}
+                }
+            }
+        });
+    }
+
+    async fn queue_deliveries(&self, execution_id: &str, status: ExecutionStatus) -> Result<()> {
+        let execution = self.executions.get_execution(execution_id).await?;
+        let mut queued = false;
+        for target in self
+            .notification_repo
+            .list_targets_for_plugin(&execution.plugin_id)
+            .await?
+        {
+            if !Self::parse_events(&target).contains(&status) {
+                continue;
+            }
+            queued |= self.queue_delivery(&target, &execution, status).await?;
+        }
+        if queued {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    // 状态广播会丢弃消息，启动时和接收落后时按数据库补齐遗漏的投递
+    async fn catch_up(&self) {
+        if let Err(err) = self.queue_missed().await {
+            tracing::error!("Failed to queue missed notifications: {}", err);
+        }
+    }
+
+    async fn queue_missed(&self) -> Result<()> {
+        let mut queued = false;
+        for target in self.notification_repo.list_targets().await? {
+            if !target.enabled {
+                continue;
+            }
+            let events = Self::parse_events(&target);
+            let missed = self
+                .notification_repo
+                .list_missed(&target, &events, target.updated_at)
+                .await?;
+            for (execution_id, status) in missed {
+                let execution = match self.executions.get_execution(&execution_id).await {
+                    Ok(execution) => execution,
+                    Err(AppError::ExecutionNotFound(_)) => continue,
+                    Err(err) => return Err(err),
+                };
+                queued |= self.queue_delivery(&target, &execution, status).await?;
+            }
+        }
+        if queued {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    async fn queue_delivery(
+        &self,
+        target: &NotificationTarget,
+        execution: &Execution,
+        status: ExecutionStatus,
+    ) -> Result<bool> {
+        let now = Utc::now().timestamp_millis();
+        let id = Uuid::new_v4().to_string();
+        let payload = self.payload(&id, status, execution).await?;
+        let delivery = NotificationDelivery {
+            id,
+            target_id: target.id.clone(),
+            execution_id: execution.id.clone(),
+            event: status,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            next_attempt_at: Some(now),
+            created_at: now,
+            delivered_at: None,
+        };
+        // 同一状态可能被发布多次，唯一约束保证只投递一次
+        self.notification_repo.create_delivery(&delivery).await
+    }
+
+    fn parse_events(target: &NotificationTarget) -> Vec<ExecutionStatus> {
+        serde_json::from_str(&target.events).unwrap_or_default()
+    }
+
+    async fn payload(
+        &self,
+        delivery_id: &str,
+        status: ExecutionStatus,
+        execution: &Execution,
+    ) -> Result<String> {
+        let plugin_name = match self.plugin_repo.get(&execution.plugin_id).await {
+            Ok(plugin) => Some(plugin.name),
+            Err(AppError::PluginNotFound(_)) => None,
+            Err(err) => return Err(err),
+        };
+        let result = execution
+            .result
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<Value>(raw).ok());
+        let payload = json!({
+            "delivery_id": delivery_id,
+            "event": status,
+            "timestamp": Utc::now().timestamp_millis(),
+            "execution": {
+                "id": execution.id,
+                "plugin_id": execution.plugin_id,
+                "plugin_name": plugin_name,
+                "plugin_version": execution.plugin_version,
+                "phase": execution.phase,
+                "status": execution.status,
+                "exit_code": execution.exit_code,
+                "error_message": execution.error_message,
+                "result": result,
+                "triggered_by": execution.triggered_by,
+                "started_at": execution.started_at,
+                "finished_at": execution.finished_at,
+                "duration_ms": execution.finished_at.map(|end| end - execution.started_at),
+            },
+            "link": format!("{}/api/executions/{}", self.public_url, execution.id),
+        });
+        Ok(payload.to_string())
+    }
+
+    async fn send_due(&self) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        for delivery in self
+            .notification_repo
+            .claim_due(now, now + LEASE_MS)
+            .await?
+        {
+            let service = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = service.send(&delivery).await {
+                    tracing::error!("Failed to record delivery {}: {}", delivery.id, err);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn send(&self, delivery: &NotificationDelivery) -> Result<()> {
+        let target = match self.notification_repo.get_target(&delivery.target_id).await {
+            Ok(target) => target,
+            // 目标已删除，投递记录随之级联删除
+            Err(AppError::NotificationTargetNotFound(_)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        let event = serde_json::to_value(delivery.event)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut request = self
+            .client
+            .post(&target.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-AntHill-Event", event)
+            .header("X-AntHill-Delivery", &delivery.id)
+            .body(delivery.payload.clone());
+        if let Some(secret) = target.secret.as_deref() {
+            request = request.header(
+                "X-AntHill-Signature",
+                format!("sha256={}", Self::sign(secret, &delivery.payload)),
+            );
+        }
+
+        let (response_status, error) = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                return self
+                    .notification_repo
+                    .mark_delivered(&delivery.id, i64::from(response.status().as_u16()))
+                    .await;
+            }
+            Ok(response) => (
+                Some(i64::from(response.status().as_u16())),
+                format!("Target answered with HTTP {}", response.status()),
+            ),
+            Err(err) => (None, err.to_string()),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let next_attempt_at =
+            Self::retry_delay(attempts).map(|delay| Utc::now().timestamp_millis() + delay);
+        tracing::warn!(
+            "Delivery {} to {} failed (attempt {}): {}",
+            delivery.id,
+            target.url,
+            attempts,
+            error
+        );
+        self.notification_repo
+            .mark_attempt_failed(&delivery.id, response_status, &error, next_attempt_at)
+            .await
+    }
+
+    // 第 n 次失败后等待 RETRY_BASE_MS * 2^(n-1)，最多 RETRY_MAX_MS，用完次数后不再重试
+    fn retry_delay(attempts: i64) -> Option<i64> {
+        (attempts < MAX_ATTEMPTS).then(|| {
+            RETRY_BASE_MS
+                .saturating_mul(1 << (attempts - 1).min(20))
+                .min(RETRY_MAX_MS)
+        })
+    }
+
+    fn sign(secret: &str, payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    async fn validate(&self, settings: &NotificationTargetSettings) -> Result<()> {
+        if settings.name.trim().is_empty() {
+            return Err(AppError::Notification(
+                "Notification target name cannot be empty".to_string(),
+            ));
+        }
+        let url = reqwest::Url::parse(&settings.url).map_err(|e| {
+            AppError::Notification(format!("Invalid URL '{}': {}", settings.url, e))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::Notification(
+                "Notification URL must use http or https".to_string(),
+            ));
+        }
+        if settings.events.is_empty() {
+            return Err(AppError::Notification(
+                "Select at least one event".to_string(),
+            ));
+        }
+        if let Some(plugin_id) = &settings.plugin_id {
+            self.plugin_repo.get(plugin_id).await?;
+        }
+        Ok(())
+    }
+
+    fn serialize_events(events: &[ExecutionStatus]) -> Result<String> {
+        serde_json::to_string(events)
+            .map_err(|e| AppError::Notification(format!("Failed to serialize events: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_payloads_with_hmac_sha256() {
+        // RFC 4231 测试用例 2
+        assert_eq!(
+            Notifier::sign("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_ne!(
+            Notifier::sign("other", "what do ya want for nothing?"),
+            Notifier::sign("Jefe", "what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn retry_delay_doubles_until_attempts_run_out() {
+        let delays: Vec<Option<i64>> = (1..=MAX_ATTEMPTS).map(Notifier::retry_delay).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(10_000),
+                Some(20_000),
+                Some(40_000),
+                Some(80_000),
+                Some(160_000),
+                None
+            ]
+        );
+        assert!((1..MAX_ATTEMPTS).all(|attempts| {
+            Notifier::retry_delay(attempts).is_some_and(|delay| delay <= RETRY_MAX_MS)
+        }));
+    }
+}
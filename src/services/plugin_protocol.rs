@@ -0,0 +1,133 @@
+// 插件在 stdout 逐行输出 ::anthill::{"type":...} 事件，没有前缀的行是普通日志
+
+use crate::models::{ExecutionProgress, PluginParameter};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const EVENT_PREFIX: &str = "::anthill::";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginEvent {
+    Log {
+        #[serde(default)]
+        level: Option<String>,
+        message: String,
+    },
+    Progress {
+        #[serde(default)]
+        percent: Option<f64>,
+        #[serde(default)]
+        step: Option<String>,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    Preview {
+        data: Value,
+    },
+    Result {
+        data: Value,
+    },
+    Error {
+        message: String,
+    },
+    InputRequest(InputRequest),
+}
+
+// 回答以一行 JSON 写入 stdin：{"name": <参数名>, "value": <回答>}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputRequest {
+    pub prompt: String,
+    pub parameter: PluginParameter,
+}
+
+impl PluginEvent {
+    pub fn progress(&self) -> Option<ExecutionProgress> {
+        let PluginEvent::Progress {
+            percent,
+            step,
+            message,
+        } = self
+        else {
+            return None;
+        };
+        Some(ExecutionProgress {
+            percent: percent
+                .filter(|percent| percent.is_finite())
+                .map(|percent| percent.clamp(0.0, 100.0)),
+            step: step.clone(),
+            message: message.clone(),
+        })
+    }
+}
+
+// 前缀后不是合法事件的行按普通文本保留
+pub fn parse_event(line: &str) -> Option<PluginEvent> {
+    let payload = line.strip_prefix(EVENT_PREFIX)?;
+    match serde_json::from_str(payload.trim()) {
+        Ok(event) => Some(event),
+        Err(e) => {
+            tracing::warn!("Ignoring malformed plugin event: {}", e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PluginOutput {
+    // 从未输出事件的插件按兼容模式处理，整个 stdout 作为预览计划
+    pub saw_events: bool,
+    // 兼容模式下完整的 stdout，不受数据库输出上限截断
+    pub plain_stdout: String,
+    pub preview: Option<String>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PluginOutput {
+    pub fn record(&mut self, event: &PluginEvent) {
+        if !self.saw_events {
+            self.plain_stdout = String::new();
+        }
+        self.saw_events = true;
+        match event {
+            PluginEvent::Preview { data } => self.preview = Some(data.to_string()),
+            PluginEvent::Result { data } => self.result = Some(data.to_string()),
+            PluginEvent::Error { message } => self.error = Some(message.clone()),
+            PluginEvent::Log { .. }
+            | PluginEvent::Progress { .. }
+            | PluginEvent::InputRequest(_) => {}
+        }
+    }
+
+    pub fn record_plain(&mut self, text: &str) {
+        if !self.saw_events {
+            self.plain_stdout.push_str(text);
+        }
+    }
+
+    pub fn preview_payload(&self) -> Option<String> {
+        if self.saw_events {
+            return self.preview.clone();
+        }
+        Some(self.plain_stdout.clone()).filter(|stdout| !stdout.is_empty())
+    }
+}
+
+pub fn log_text(event: &PluginEvent) -> Option<String> {
+    match event {
+        PluginEvent::Log {
+            level: Some(level),
+            message,
+        } => Some(format!("[{}] {}", level, message)),
+        PluginEvent::Log {
+            level: None,
+            message,
+        } => Some(message.clone()),
+        PluginEvent::Error { message } => Some(format!("[error] {}", message)),
+        PluginEvent::InputRequest(request) => Some(format!("[input] {}", request.prompt)),
+        PluginEvent::Progress { .. } | PluginEvent::Preview { .. } | PluginEvent::Result { .. } => {
+            None
+        }
+    }
+}
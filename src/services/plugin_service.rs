@@ -1,6 +1,8 @@
 use crate::error::{AppError, Result};
+use crate::executor::{ExecutorRegistry, find_program};
 use crate::models::{
-    Plugin, PluginParamType, PluginParameter, PluginParameterGroup, PluginType, PythonDependencies,
+    DenoPermissions, JsPackageManager, Plugin, PluginDependencies, PluginParamType,
+    PluginParameter, PluginParameterGroup, PluginType, PythonDependencies,
 };
 use crate::paths;
 use crate::repository::PluginRepository;
@@ -20,12 +22,30 @@ struct PackageMetadata {
     version: String,
     min_anthill_version: Option<String>,
     plugin_type: String,
+    // JavaScript 运行时：node（默认）、deno 或 bun
+    runtime: Option<String>,
+    // 覆盖入口文件 shebang 的解释器
+    interpreter: Option<String>,
+    permissions: Option<DenoPermissions>,
     description: String,
     author: String,
     entry_point: String,
     parameters: Option<Vec<PluginParameter>>,
     groups: Option<Vec<PluginParameterGroup>>,
     metadata: Option<serde_json::Value>,
+    timeout_seconds: Option<PackageTimeouts>,
+    max_concurrency: Option<u32>,
+}
+
+// timeout_seconds 可以是两个阶段共用的一个值，也可以分阶段设置
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PackageTimeouts {
+    All(u64),
+    Phases {
+        prepare: Option<u64>,
+        apply: Option<u64>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,18 +54,33 @@ enum PackageMetadataPayload {
     Multi {
         install_plugins: Vec<PackageMetadata>,
     },
-    Single(PackageMetadata),
+    Single(Box<PackageMetadata>),
 }
 
 #[derive(Clone)]
 pub struct PluginService {
     repo: PluginRepository,
     uv_path: Option<PathBuf>,
+    python_path: Option<PathBuf>,
+    js_package_manager: JsPackageManager,
+    executors: ExecutorRegistry,
 }
 
 impl PluginService {
-    pub fn new(repo: PluginRepository, uv_path: Option<PathBuf>) -> Self {
-        Self { repo, uv_path }
+    pub fn new(
+        repo: PluginRepository,
+        uv_path: Option<PathBuf>,
+        python_path: Option<PathBuf>,
+        js_package_manager: JsPackageManager,
+        executors: ExecutorRegistry,
+    ) -> Self {
+        Self {
+            repo,
+            uv_path,
+            python_path,
+            js_package_manager,
+            executors,
+        }
     }
 
     pub async fn list_plugins(&self) -> Result<Vec<Plugin>> {
@@ -82,12 +117,17 @@ impl PluginService {
             version,
             min_anthill_version,
             plugin_type,
+            runtime,
+            interpreter,
+            permissions,
             description: _,
             author: _,
             entry_point,
             parameters,
             groups,
             metadata,
+            timeout_seconds,
+            max_concurrency,
         } = spec;
 
         let plugin_id = Self::normalize_plugin_id(plugin_id, &name)?;
@@ -102,12 +142,22 @@ impl PluginService {
                 "Entry point cannot be empty".to_string(),
             ));
         }
-        let _ = Self::parse_plugin_type(&plugin_type)?;
+        let (plugin_type, runtime) = self.resolve_plugin_type(&plugin_type, runtime)?;
+        let _ = Self::serialize_permissions(&runtime, permissions)?;
         let _ = Self::validate_parameters(parameters)?;
         let _ = Self::validate_groups(groups)?;
         let _ = Self::serialize_metadata(metadata)?;
         let _ = Self::normalize_min_anthill_version(min_anthill_version)?;
-        let _ = Self::resolve_entry_point(&entry_point, temp_dir.path(), metadata_dir.as_deref())?;
+        let _ = Self::normalize_timeouts(timeout_seconds)?;
+        let _ = Self::validate_max_concurrency(max_concurrency)?;
+        let entry_point =
+            Self::resolve_entry_point(&entry_point, temp_dir.path(), metadata_dir.as_deref())?;
+        Self::validate_runtime_entry_point(&runtime, &entry_point)?;
+        let _ = Self::resolve_interpreter(
+            plugin_type,
+            interpreter,
+            &temp_dir.path().join(&entry_point),
+        )?;
         Self::ensure_newer_version(&version, &existing.version)?;
 
         self.uninstall_plugin(id).await?;
@@ -123,13 +173,13 @@ impl PluginService {
                 Err(err) => return Err(err.into()),
             }
         }
-        if let Some(venv_path) = &plugin.python_venv_path {
-            if !venv_path.is_empty() {
-                match fs::remove_dir_all(venv_path) {
-                    Ok(_) => {}
-                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
-                    Err(err) => return Err(err.into()),
-                }
+        if let Some(venv_path) = &plugin.python_venv_path
+            && !venv_path.is_empty()
+        {
+            match fs::remove_dir_all(venv_path) {
+                Ok(_) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
             }
         }
         self.repo.delete(id).await
@@ -151,12 +201,17 @@ impl PluginService {
             version,
             min_anthill_version,
             plugin_type,
+            runtime,
+            interpreter,
+            permissions,
             description,
             author,
             entry_point,
             parameters,
             groups,
             metadata,
+            timeout_seconds,
+            max_concurrency,
         } = spec;
 
         let plugin_id = Self::normalize_plugin_id(plugin_id, &name)?;
@@ -172,11 +227,15 @@ impl PluginService {
             ));
         }
 
-        let plugin_type = Self::parse_plugin_type(&plugin_type)?;
+        let (plugin_type, runtime) = self.resolve_plugin_type(&plugin_type, runtime)?;
+        let permissions_json = Self::serialize_permissions(&runtime, permissions)?;
         let parameters_json = Self::validate_parameters(parameters)?;
         let groups_json = Self::validate_groups(groups)?;
         let metadata_json = Self::serialize_metadata(metadata)?;
         let min_anthill_version = Self::normalize_min_anthill_version(min_anthill_version)?;
+        let (prepare_timeout_seconds, apply_timeout_seconds) =
+            Self::normalize_timeouts(timeout_seconds)?;
+        let max_concurrency = Self::validate_max_concurrency(max_concurrency)?;
 
         let internal_id = Uuid::new_v4().to_string();
         let plugin_dir = Self::plugin_dir_for(&plugin_id)?;
@@ -203,9 +262,25 @@ impl PluginService {
                     return Err(err);
                 }
             };
+        if let Err(err) = Self::validate_runtime_entry_point(&runtime, &entry_point) {
+            let _ = fs::remove_dir_all(&plugin_dir);
+            return Err(err);
+        }
+        let interpreter = match Self::resolve_interpreter(
+            plugin_type,
+            interpreter,
+            &plugin_dir.join(&entry_point),
+        ) {
+            Ok(interpreter) => interpreter,
+            Err(err) => {
+                let _ = fs::remove_dir_all(&plugin_dir);
+                return Err(err);
+            }
+        };
 
         let mut python_venv_path = None;
         let mut python_dependencies_json = None;
+        let mut dependencies_json = None;
         if plugin_type == PluginType::Python {
             let venv_dir = Self::python_env_dir_for(&plugin_id)?;
             let resolved_deps = Self::resolve_python_dependencies(
@@ -224,8 +299,20 @@ impl PluginService {
                 },
                 None => None,
             };
+            dependencies_json = match resolved_deps.as_ref() {
+                Some(deps) => match Self::serialize_dependencies(&deps.into()) {
+                    Ok(json) => Some(json),
+                    Err(err) => {
+                        let _ = fs::remove_dir_all(&plugin_dir);
+                        let _ = fs::remove_dir_all(&venv_dir);
+                        return Err(err);
+                    }
+                },
+                None => None,
+            };
             if let Err(err) = Self::prepare_python_env(
                 self.uv_path.as_deref(),
+                self.python_path.as_deref(),
                 &venv_dir,
                 &plugin_dir,
                 resolved_deps.as_ref(),
@@ -239,6 +326,29 @@ impl PluginService {
             python_venv_path = Some(venv_dir.to_string_lossy().to_string());
         }
 
+        if plugin_type == PluginType::JavaScript
+            && let Some(deps) = Self::resolve_js_dependencies(
+                &plugin_dir,
+                metadata_dir.as_deref(),
+                &entry_point,
+                self.js_package_manager,
+            )
+        {
+            let installed = match Self::serialize_dependencies(&deps) {
+                Ok(json) => Self::install_js_dependencies(&plugin_dir, &deps)
+                    .await
+                    .map(|_| json),
+                Err(err) => Err(err),
+            };
+            match installed {
+                Ok(json) => dependencies_json = Some(json),
+                Err(err) => {
+                    let _ = fs::remove_dir_all(&plugin_dir);
+                    return Err(err);
+                }
+            }
+        }
+
         let now = Utc::now().timestamp_millis();
         let plugin = Plugin {
             id: internal_id,
@@ -247,6 +357,9 @@ impl PluginService {
             version,
             min_anthill_version,
             plugin_type,
+            runtime: Some(runtime),
+            interpreter,
+            permissions: permissions_json,
             description,
             author,
             plugin_path: plugin_dir.to_string_lossy().to_string(),
@@ -259,6 +372,10 @@ impl PluginService {
             metadata: metadata_json,
             python_venv_path,
             python_dependencies: python_dependencies_json,
+            dependencies: dependencies_json,
+            prepare_timeout_seconds,
+            apply_timeout_seconds,
+            max_concurrency,
         };
 
         if let Err(err) = self.repo.create(&plugin).await {
@@ -330,6 +447,15 @@ impl PluginService {
             let mut buffer = Vec::new();
             file.read_to_end(&mut buffer)?;
             outfile.write_all(&buffer)?;
+
+            // 只保留可执行位，其余权限按默认值
+            #[cfg(unix)]
+            if let Some(mode) = file.unix_mode()
+                && mode & 0o111 != 0
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&out_path, fs::Permissions::from_mode(0o755))?;
+            }
         }
 
         Ok(())
@@ -378,7 +504,7 @@ impl PluginService {
         let payload: PackageMetadataPayload = serde_json::from_slice(&buffer)
             .map_err(|e| AppError::Execution(format!("Invalid metadata JSON: {}", e)))?;
         let spec = match payload {
-            PackageMetadataPayload::Single(spec) => spec,
+            PackageMetadataPayload::Single(spec) => *spec,
             PackageMetadataPayload::Multi { install_plugins } => {
                 if install_plugins.len() != 1 {
                     return Err(AppError::Execution(
@@ -437,7 +563,7 @@ impl PluginService {
         let payload: PackageMetadataPayload = serde_json::from_slice(&buffer)
             .map_err(|e| AppError::Execution(format!("Invalid metadata JSON: {}", e)))?;
         let spec = match payload {
-            PackageMetadataPayload::Single(spec) => spec,
+            PackageMetadataPayload::Single(spec) => *spec,
             PackageMetadataPayload::Multi { install_plugins } => {
                 if install_plugins.len() != 1 {
                     return Err(AppError::Execution(
@@ -505,12 +631,79 @@ impl PluginService {
         Some(PathBuf::from(url))
     }
 
-    fn parse_plugin_type(raw: &str) -> Result<PluginType> {
-        match raw {
-            "python" => Ok(PluginType::Python),
-            "javascript" | "js" => Ok(PluginType::JavaScript),
-            _ => Err(AppError::InvalidPluginType),
+    // 内置类型以外的名称必须是已注册的运行时
+    fn resolve_plugin_type(
+        &self,
+        raw: &str,
+        runtime: Option<String>,
+    ) -> Result<(PluginType, String)> {
+        let plugin_type = match raw {
+            "python" => PluginType::Python,
+            "javascript" | "js" => PluginType::JavaScript,
+            "shell" => PluginType::Shell,
+            "executable" => PluginType::Executable,
+            _ if self.executors.contains(raw) && runtime.is_none() => {
+                return Ok((PluginType::Custom, raw.to_string()));
+            }
+            _ if self.executors.contains(raw) => {
+                return Err(AppError::Execution(
+                    "runtime is only supported for javascript plugins".to_string(),
+                ));
+            }
+            _ => return Err(AppError::InvalidPluginType),
+        };
+        let runtime = runtime
+            .map(|runtime| runtime.trim().to_ascii_lowercase())
+            .filter(|runtime| !runtime.is_empty());
+        match (plugin_type, runtime) {
+            (PluginType::JavaScript, Some(runtime)) => match runtime.as_str() {
+                "node" | "deno" | "bun" => Ok((plugin_type, runtime)),
+                _ => Err(AppError::Execution(format!(
+                    "Unsupported javascript runtime '{}', expected node, deno or bun",
+                    runtime
+                ))),
+            },
+            (_, Some(_)) => Err(AppError::Execution(
+                "runtime is only supported for javascript plugins".to_string(),
+            )),
+            (_, None) => {
+                let runtime = plugin_type
+                    .default_runtime()
+                    .ok_or(AppError::InvalidPluginType)?;
+                Ok((plugin_type, runtime.to_string()))
+            }
+        }
+    }
+
+    // Node 不能直接运行 TypeScript，需要 Deno 或 Bun
+    fn validate_runtime_entry_point(runtime: &str, entry_point: &str) -> Result<()> {
+        let is_typescript = Path::new(entry_point)
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| matches!(ext, "ts" | "tsx" | "mts" | "cts"));
+        if is_typescript && runtime == "node" {
+            return Err(AppError::Execution(
+                "TypeScript entry points need the deno or bun runtime".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn serialize_permissions(
+        runtime: &str,
+        permissions: Option<DenoPermissions>,
+    ) -> Result<Option<String>> {
+        let Some(permissions) = permissions else {
+            return Ok(None);
+        };
+        if runtime != "deno" {
+            return Err(AppError::Execution(
+                "permissions are only supported for the deno runtime".to_string(),
+            ));
         }
+        serde_json::to_string(&permissions)
+            .map(Some)
+            .map_err(|e| AppError::Execution(format!("Failed to serialize permissions: {}", e)))
     }
 
     fn validate_entry_point(entry_point: &str) -> Result<()> {
@@ -556,6 +749,59 @@ impl PluginService {
         )))
     }
 
+    // 优先使用声明的解释器，否则读取入口文件的 shebang；可执行文件必须有执行权限
+    fn resolve_interpreter(
+        plugin_type: PluginType,
+        interpreter: Option<String>,
+        entry_path: &Path,
+    ) -> Result<Option<String>> {
+        let interpreter = interpreter
+            .map(|interpreter| interpreter.trim().to_string())
+            .filter(|interpreter| !interpreter.is_empty());
+        match plugin_type {
+            PluginType::Shell => match interpreter {
+                Some(interpreter) => Ok(Some(interpreter)),
+                None => Self::read_shebang(entry_path),
+            },
+            _ if interpreter.is_some() => Err(AppError::Execution(
+                "interpreter is only supported for shell plugins".to_string(),
+            )),
+            PluginType::Executable => {
+                Self::ensure_executable(entry_path)?;
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn read_shebang(path: &Path) -> Result<Option<String>> {
+        let mut head = Vec::new();
+        fs::File::open(path)?.take(512).read_to_end(&mut head)?;
+        let first_line = head.split(|byte| *byte == b'\n').next().unwrap_or_default();
+        let Some(shebang) = first_line.strip_prefix(b"#!") else {
+            return Ok(None);
+        };
+        let shebang = String::from_utf8_lossy(shebang).trim().to_string();
+        Ok((!shebang.is_empty()).then_some(shebang))
+    }
+
+    #[cfg(unix)]
+    fn ensure_executable(path: &Path) -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        if fs::metadata(path)?.permissions().mode() & 0o111 == 0 {
+            return Err(AppError::Execution(format!(
+                "Entry point is not executable: {}",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn ensure_executable(_path: &Path) -> Result<()> {
+        Ok(())
+    }
+
     fn normalize_plugin_id(plugin_id: Option<String>, name: &str) -> Result<String> {
         let plugin_id_raw = plugin_id.unwrap_or_else(|| name.to_string());
         let plugin_id = plugin_id_raw.trim();
@@ -630,6 +876,35 @@ impl PluginService {
         Ok(Some(required.to_string()))
     }
 
+    fn normalize_timeouts(raw: Option<PackageTimeouts>) -> Result<(Option<i64>, Option<i64>)> {
+        let (prepare, apply) = match raw {
+            None => return Ok((None, None)),
+            Some(PackageTimeouts::All(seconds)) => (Some(seconds), Some(seconds)),
+            Some(PackageTimeouts::Phases { prepare, apply }) => (prepare, apply),
+        };
+        let check = |seconds: Option<u64>| -> Result<Option<i64>> {
+            match seconds {
+                None => Ok(None),
+                Some(0) => Err(AppError::Execution(
+                    "timeout_seconds must be greater than 0".to_string(),
+                )),
+                Some(seconds) => i64::try_from(seconds).map(Some).map_err(|_| {
+                    AppError::Execution(format!("timeout_seconds is too large: {}", seconds))
+                }),
+            }
+        };
+        Ok((check(prepare)?, check(apply)?))
+    }
+
+    fn validate_max_concurrency(raw: Option<u32>) -> Result<Option<i64>> {
+        match raw {
+            Some(0) => Err(AppError::Execution(
+                "max_concurrency must be greater than 0".to_string(),
+            )),
+            other => Ok(other.map(i64::from)),
+        }
+    }
+
     fn validate_plugin_id(plugin_id: &str) -> Result<()> {
         if plugin_id.contains('/') || plugin_id.contains('\\') {
             return Err(crate::error::AppError::Execution(
@@ -669,16 +944,7 @@ impl PluginService {
         metadata_dir: Option<&Path>,
         entry_point: &str,
     ) -> Option<PythonDependencies> {
-        let mut search_dirs: Vec<PathBuf> = Vec::new();
-        if let Some(dir) = metadata_dir {
-            Self::push_unique_dir(&mut search_dirs, dir.to_path_buf());
-        }
-        if let Some(entry_dir) = Path::new(entry_point).parent() {
-            if !entry_dir.as_os_str().is_empty() {
-                Self::push_unique_dir(&mut search_dirs, entry_dir.to_path_buf());
-            }
-        }
-        Self::push_unique_dir(&mut search_dirs, PathBuf::new());
+        let search_dirs = Self::dependency_search_dirs(metadata_dir, entry_point);
 
         if let Some(path) =
             Self::find_dependency_in_dirs(plugin_dir, &search_dirs, "pyproject.toml")
@@ -695,6 +961,43 @@ impl PluginService {
         None
     }
 
+    fn resolve_js_dependencies(
+        plugin_dir: &Path,
+        metadata_dir: Option<&Path>,
+        entry_point: &str,
+        manager: JsPackageManager,
+    ) -> Option<PluginDependencies> {
+        let search_dirs = Self::dependency_search_dirs(metadata_dir, entry_point);
+        let path = Self::find_dependency_in_dirs(plugin_dir, &search_dirs, "package.json")?;
+        let package_dir = Path::new(&path).parent().unwrap_or(Path::new(""));
+        let lockfile = manager
+            .lockfiles()
+            .iter()
+            .map(|name| package_dir.join(name))
+            .find(|relative| plugin_dir.join(relative).is_file())
+            .map(|relative| relative.to_string_lossy().to_string());
+        Some(PluginDependencies::PackageJson {
+            path,
+            locked: lockfile.is_some(),
+            lockfile,
+            manager,
+        })
+    }
+
+    fn dependency_search_dirs(metadata_dir: Option<&Path>, entry_point: &str) -> Vec<PathBuf> {
+        let mut search_dirs: Vec<PathBuf> = Vec::new();
+        if let Some(dir) = metadata_dir {
+            Self::push_unique_dir(&mut search_dirs, dir.to_path_buf());
+        }
+        if let Some(entry_dir) = Path::new(entry_point).parent()
+            && !entry_dir.as_os_str().is_empty()
+        {
+            Self::push_unique_dir(&mut search_dirs, entry_dir.to_path_buf());
+        }
+        Self::push_unique_dir(&mut search_dirs, PathBuf::new());
+        search_dirs
+    }
+
     fn push_unique_dir(target: &mut Vec<PathBuf>, dir: PathBuf) {
         if !target.iter().any(|existing| existing == &dir) {
             target.push(dir);
@@ -725,8 +1028,52 @@ impl PluginService {
         })
     }
 
+    fn serialize_dependencies(deps: &PluginDependencies) -> Result<String> {
+        serde_json::to_string(deps).map_err(|e| {
+            AppError::Execution(format!("Failed to serialize plugin dependencies: {}", e))
+        })
+    }
+
+    async fn install_js_dependencies(plugin_dir: &Path, deps: &PluginDependencies) -> Result<()> {
+        let PluginDependencies::PackageJson {
+            path,
+            locked,
+            manager,
+            ..
+        } = deps
+        else {
+            return Ok(());
+        };
+        let package_json = plugin_dir.join(path);
+        let package_dir = package_json.parent().unwrap_or(plugin_dir);
+        let program = manager.program();
+        if !locked {
+            tracing::warn!(
+                "{} has no {} lockfile, installing dependencies without locked versions",
+                package_json.display(),
+                program
+            );
+        }
+        let args: Vec<String> = manager
+            .install_args(*locked)
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        // Windows 上 npm/pnpm 是 .cmd 脚本，需要按 PATHEXT 解析出完整路径
+        let mut cmd = tokio::process::Command::new(
+            find_program(program).unwrap_or_else(|| PathBuf::from(program)),
+        );
+        cmd.args(&args);
+        cmd.current_dir(package_dir);
+        // 非交互环境，避免 pnpm 等等待确认
+        cmd.env("CI", "true");
+        Self::run_install_command(cmd, program, &args).await
+    }
+
     async fn prepare_python_env(
         uv_path: Option<&Path>,
+        interpreter: Option<&Path>,
         venv_dir: &Path,
         plugin_dir: &Path,
         dependencies: Option<&PythonDependencies>,
@@ -735,8 +1082,14 @@ impl PluginService {
             fs::create_dir_all(parent)?;
         }
 
-        let venv_dir_str = venv_dir.to_string_lossy().to_string();
-        Self::run_uv_command(uv_path, &vec!["venv".to_string(), venv_dir_str], None).await?;
+        let mut venv_args = vec!["venv".to_string()];
+        // 配置了 python_path 时用它创建 venv，否则由 uv 自行选择解释器
+        if let Some(interpreter) = interpreter {
+            venv_args.push("--python".to_string());
+            venv_args.push(interpreter.to_string_lossy().to_string());
+        }
+        venv_args.push(venv_dir.to_string_lossy().to_string());
+        Self::run_uv_command(uv_path, &venv_args, None).await?;
 
         let python_path = Self::python_executable_path(venv_dir);
         if !python_path.is_file() {
@@ -797,8 +1150,22 @@ impl PluginService {
         if let Some(dir) = current_dir {
             cmd.current_dir(dir);
         }
+        Self::run_install_command(cmd, "uv", args).await
+    }
+
+    // 失败时报告 stderr（为空时用 stdout）
+    async fn run_install_command(
+        mut cmd: tokio::process::Command,
+        program: &str,
+        args: &[String],
+    ) -> Result<()> {
         let output = cmd.output().await.map_err(|e| {
-            crate::error::AppError::Execution(format!("Failed to run uv {}: {}", args.join(" "), e))
+            crate::error::AppError::Execution(format!(
+                "Failed to run {} {}: {}",
+                program,
+                args.join(" "),
+                e
+            ))
         })?;
 
         if output.status.success() {
@@ -813,9 +1180,9 @@ impl PluginService {
             stdout.trim()
         };
         let message = if details.is_empty() {
-            format!("uv {} failed", args.join(" "))
+            format!("{} {} failed", program, args.join(" "))
         } else {
-            format!("uv {} failed: {}", args.join(" "), details)
+            format!("{} {} failed: {}", program, args.join(" "), details)
         };
         Err(crate::error::AppError::Execution(message))
     }
@@ -845,13 +1212,13 @@ impl PluginService {
                     name
                 )));
             }
-            if let Some(default) = &param.default {
-                if !param.param_type.matches(default) {
-                    return Err(crate::error::AppError::Execution(format!(
-                        "Default value for parameter '{}' does not match type {:?}",
-                        name, param.param_type
-                    )));
-                }
+            if let Some(default) = &param.default
+                && !param.param_type.matches(default)
+            {
+                return Err(crate::error::AppError::Execution(format!(
+                    "Default value for parameter '{}' does not match type {:?}",
+                    name, param.param_type
+                )));
             }
             if let Some(choices) = &param.choices {
                 if choices.is_empty() {
@@ -902,11 +1269,10 @@ impl PluginService {
                                 }
                                 if let Some(value) =
                                     choice.as_object().and_then(|obj| obj.get("value"))
+                                    && value == item
                                 {
-                                    if value == item {
-                                        item_matches = true;
-                                        break;
-                                    }
+                                    item_matches = true;
+                                    break;
                                 }
                             }
                             if !item_matches {
@@ -923,13 +1289,11 @@ impl PluginService {
                                 default_matches = true;
                                 break;
                             }
-                            if let Some(value) =
-                                choice.as_object().and_then(|obj| obj.get("value"))
+                            if let Some(value) = choice.as_object().and_then(|obj| obj.get("value"))
+                                && value == default
                             {
-                                if value == default {
-                                    default_matches = true;
-                                    break;
-                                }
+                                default_matches = true;
+                                break;
                             }
                         }
                         if !default_matches {
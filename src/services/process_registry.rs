@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+use tokio::process::Child;
+use tokio::sync::oneshot;
+use tokio::time::{Duration, sleep, timeout};
+
+// SIGTERM 之后等待插件退出的时间，超时后强制终止
+pub const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// 重启后接管的进程无法 wait，按该间隔轮询
+const ORPHAN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 监督任务收到后终止进程树，记录最终状态后通过内层 sender 回复
+pub type StopRequest = oneshot::Sender<()>;
+
+struct RunningProcess {
+    pid: u32,
+    stop_tx: oneshot::Sender<StopRequest>,
+}
+
+// 监督任务持有 Child 并等待它退出，这里只保存 pid 和通知监督任务的通道
+#[derive(Clone, Default)]
+pub struct ProcessRegistry {
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+}
+
+impl ProcessRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, execution_id: &str, pid: u32) -> oneshot::Receiver<StopRequest> {
+        let (stop_tx, stop_rx) = oneshot::channel();
+        self.processes
+            .lock()
+            .unwrap()
+            .insert(execution_id.to_string(), RunningProcess { pid, stop_tx });
+        stop_rx
+    }
+
+    pub fn unregister(&self, execution_id: &str) {
+        self.processes.lock().unwrap().remove(execution_id);
+    }
+
+    // 等待监督任务记录结果后返回，没有对应进程时返回 false
+    pub async fn stop(&self, execution_id: &str) -> bool {
+        let Some(process) = self.processes.lock().unwrap().remove(execution_id) else {
+            return false;
+        };
+
+        tracing::info!(
+            "Stopping execution {} with pid {}",
+            execution_id,
+            process.pid
+        );
+        let (ack_tx, ack_rx) = oneshot::channel();
+        if process.stop_tx.send(ack_tx).is_err() {
+            // 进程在请求到达前已自行退出
+            return false;
+        }
+        ack_rx.await.is_ok()
+    }
+}
+
+// 先发 SIGTERM，超过 grace 后 SIGKILL，返回进程组组长的退出状态
+#[cfg(unix)]
+pub async fn terminate_tree(
+    child: &mut Child,
+    pid: u32,
+    grace: Duration,
+) -> std::io::Result<ExitStatus> {
+    let pgid = -(pid as libc::pid_t);
+    // SAFETY: kill(2) has no memory safety requirements; a stale group id only yields ESRCH.
+    unsafe {
+        libc::kill(pgid, libc::SIGTERM);
+    }
+
+    let status = match timeout(grace, child.wait()).await {
+        Ok(status) => status,
+        Err(_) => {
+            tracing::warn!(
+                "Process group {} did not exit within {:?}, killing",
+                pid,
+                grace
+            );
+            // SAFETY: see above.
+            unsafe {
+                libc::kill(pgid, libc::SIGKILL);
+            }
+            child.wait().await
+        }
+    };
+
+    // 清理组长退出后留在进程组中的其他进程
+    // SAFETY: see above.
+    unsafe {
+        libc::kill(pgid, libc::SIGKILL);
+    }
+    status
+}
+
+#[cfg(windows)]
+pub async fn terminate_tree(
+    child: &mut Child,
+    pid: u32,
+    grace: Duration,
+) -> std::io::Result<ExitStatus> {
+    let _ = tokio::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T"])
+        .output()
+        .await;
+
+    match timeout(grace, child.wait()).await {
+        Ok(status) => status,
+        Err(_) => {
+            tracing::warn!(
+                "Process tree {} did not exit within {:?}, killing",
+                pid,
+                grace
+            );
+            let _ = tokio::process::Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .output()
+                .await;
+            child.kill().await?;
+            child.wait().await
+        }
+    }
+}
+
+// 插件以进程组组长启动，同时检查组号可以避免 pid 被复用的误判
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: getpgid(2) only reads process table state.
+    unsafe { libc::getpgid(pid) == pid }
+}
+
+#[cfg(windows)]
+pub fn is_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: the handle is only used for the exit code query and closed right after.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut exit_code = 0u32;
+        let queried = GetExitCodeProcess(handle, &mut exit_code) != 0;
+        CloseHandle(handle);
+        queried && exit_code == STILL_ACTIVE as u32
+    }
+}
+
+pub async fn wait_for_exit(pid: u32) {
+    while is_alive(pid) {
+        sleep(ORPHAN_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(unix)]
+pub async fn terminate_orphan(pid: u32, grace: Duration) {
+    let pgid = -(pid as libc::pid_t);
+    // SAFETY: see `terminate_tree`.
+    unsafe {
+        libc::kill(pgid, libc::SIGTERM);
+    }
+    if timeout(grace, wait_for_exit(pid)).await.is_err() {
+        tracing::warn!(
+            "Process group {} did not exit within {:?}, killing",
+            pid,
+            grace
+        );
+    }
+    // SAFETY: see `terminate_tree`.
+    unsafe {
+        libc::kill(pgid, libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+pub async fn terminate_orphan(pid: u32, grace: Duration) {
+    let _ = tokio::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T"])
+        .output()
+        .await;
+    if timeout(grace, wait_for_exit(pid)).await.is_err() {
+        let _ = tokio::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output()
+            .await;
+    }
+}
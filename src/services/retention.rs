@@ -0,0 +1,133 @@
+use crate::error::Result;
+use crate::models::{ExecutionStatus, RetentionPolicy};
+use crate::paths;
+use crate::repository::ExecutionRepository;
+use crate::services::artifact_store::{self, ArtifactStore};
+use crate::services::log_store;
+use chrono::Utc;
+use std::collections::BTreeSet;
+use tokio::time::{Duration, Instant, interval};
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const VACUUM_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+const FAILED_STATUSES: [ExecutionStatus; 2] = [ExecutionStatus::Failed, ExecutionStatus::TimedOut];
+
+#[derive(Clone)]
+pub struct RetentionService {
+    exec_repo: ExecutionRepository,
+    policy: RetentionPolicy,
+}
+
+impl RetentionService {
+    pub fn new(exec_repo: ExecutionRepository, policy: RetentionPolicy) -> Self {
+        Self { exec_repo, policy }
+    }
+
+    pub fn spawn_pruner(&self) {
+        if self.policy.is_disabled() {
+            return;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(PRUNE_INTERVAL);
+            let mut last_vacuum = Instant::now();
+            let mut pruned_since_vacuum = 0;
+            loop {
+                ticker.tick().await;
+                match service.prune().await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        tracing::info!("Pruned {} execution(s) from history", count);
+                        pruned_since_vacuum += count;
+                    }
+                    Err(err) => tracing::error!("Failed to prune execution history: {}", err),
+                }
+
+                if pruned_since_vacuum > 0 && last_vacuum.elapsed() >= VACUUM_INTERVAL {
+                    // VACUUM 会重写整个数据库文件，只在确实删除过数据后执行
+                    match service.exec_repo.vacuum().await {
+                        Ok(()) => {
+                            tracing::info!("Vacuumed database");
+                            pruned_since_vacuum = 0;
+                            last_vacuum = Instant::now();
+                        }
+                        Err(err) => tracing::error!("Failed to vacuum database: {}", err),
+                    }
+                }
+            }
+        });
+    }
+
+    pub async fn prune(&self) -> Result<usize> {
+        let now = Utc::now().timestamp_millis();
+        let mut ids = BTreeSet::new();
+
+        if let Some(days) = self.policy.max_age_days {
+            let before = now - days as i64 * DAY_MS;
+            let expired = if self.policy.failed_max_age_days.is_some() {
+                let statuses = [
+                    ExecutionStatus::Completed,
+                    ExecutionStatus::Stopped,
+                    ExecutionStatus::PreviewReady,
+                    ExecutionStatus::Expired,
+                ];
+                self.exec_repo
+                    .list_finished_before(before, None, Some(&statuses))
+                    .await?
+            } else {
+                self.exec_repo
+                    .list_finished_before(before, None, None)
+                    .await?
+            };
+            ids.extend(expired);
+        }
+
+        if let Some(days) = self.policy.failed_max_age_days {
+            let before = now - days as i64 * DAY_MS;
+            ids.extend(
+                self.exec_repo
+                    .list_finished_before(before, None, Some(&FAILED_STATUSES))
+                    .await?,
+            );
+        }
+
+        if let Some(keep) = self.policy.max_executions_per_plugin {
+            let exclude: &[ExecutionStatus] = if self.policy.failed_max_age_days.is_some() {
+                &FAILED_STATUSES
+            } else {
+                &[]
+            };
+            ids.extend(self.exec_repo.list_finished_beyond(keep, exclude).await?);
+        }
+
+        let mut deleted = 0;
+        for id in ids {
+            if self.exec_repo.delete(&id).await? {
+                deleted += 1;
+            }
+            remove_execution_files(&id);
+        }
+        Ok(deleted)
+    }
+}
+
+pub fn remove_execution_files(execution_id: &str) {
+    let work_dir = paths::work_dir().map(|dir| dir.join(execution_id));
+    let results = [
+        log_store::remove(execution_id),
+        ArtifactStore::remove(execution_id),
+        work_dir.and_then(|dir| artifact_store::remove_dir_if_exists(&dir)),
+    ];
+    for result in results {
+        if let Err(err) = result {
+            tracing::warn!(
+                "Failed to remove files of execution {}: {}",
+                execution_id,
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::executor::{ExecutorRegistry, find_program};
+use crate::models::RuntimeInfo;
+use futures_util::future::join_all;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Probe {
+    name: String,
+    program: Option<String>,
+    version_arg: Option<String>,
+    // 为空时按能否读到版本判断
+    available: Option<bool>,
+}
+
+#[derive(Clone)]
+pub struct RuntimeService {
+    executors: ExecutorRegistry,
+    uv_path: Option<PathBuf>,
+}
+
+impl RuntimeService {
+    pub fn new(executors: ExecutorRegistry, uv_path: Option<PathBuf>) -> Self {
+        Self { executors, uv_path }
+    }
+
+    pub async fn list_runtimes(&self) -> Vec<RuntimeInfo> {
+        let mut programs: Vec<Probe> = self
+            .executors
+            .list()
+            .into_iter()
+            .map(|(name, executor)| Probe {
+                name,
+                program: executor.program().map(str::to_string),
+                version_arg: executor.version_arg().map(str::to_string),
+                available: Some(executor.check_available().is_ok()),
+            })
+            .collect();
+        let uv_program = self
+            .uv_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|| "uv".to_string());
+        // uv 没有执行器可检查，能读到版本才算可用
+        programs.push(Probe {
+            name: "uv".to_string(),
+            program: Some(uv_program),
+            version_arg: Some("--version".to_string()),
+            available: None,
+        });
+
+        join_all(programs.into_iter().map(Self::probe)).await
+    }
+
+    async fn probe(probe: Probe) -> RuntimeInfo {
+        let Some(program) = probe.program else {
+            return RuntimeInfo {
+                name: probe.name,
+                path: None,
+                version: None,
+                available: probe.available.unwrap_or(false),
+            };
+        };
+        let Some(path) = find_program(&program) else {
+            return RuntimeInfo {
+                name: probe.name,
+                path: None,
+                version: None,
+                available: false,
+            };
+        };
+        let version = match &probe.version_arg {
+            Some(arg) => Self::read_version(&path, arg).await,
+            None => None,
+        };
+        RuntimeInfo {
+            name: probe.name,
+            path: Some(path.to_string_lossy().to_string()),
+            available: probe.available.unwrap_or(version.is_some()),
+            version,
+        }
+    }
+
+    // 有些解释器把版本输出到 stderr
+    async fn read_version(path: &Path, arg: &str) -> Option<String> {
+        let mut cmd = tokio::process::Command::new(path);
+        cmd.arg(arg).stdin(std::process::Stdio::null());
+        cmd.kill_on_drop(true);
+        let output = tokio::time::timeout(VERSION_TIMEOUT, cmd.output())
+            .await
+            .ok()?
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        [output.stdout, output.stderr].iter().find_map(|stream| {
+            String::from_utf8_lossy(stream)
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string)
+        })
+    }
+}
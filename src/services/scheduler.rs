@@ -0,0 +1,530 @@
+use crate::error::{AppError, Result};
+use crate::models::{ExecutionStatus, OverlapPolicy, Schedule};
+use crate::repository::ScheduleRepository;
+use crate::services::execution_service::ExecutionService;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::time::{Duration, sleep};
+use uuid::Uuid;
+
+const FINISHED_STATUSES: [ExecutionStatus; 4] = [
+    ExecutionStatus::Completed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Stopped,
+    ExecutionStatus::TimedOut,
+];
+const WAIT_INTERVAL_MS: u64 = 60_000;
+// 即使没有到期的任务也定期检查，防止时钟跳变后睡过头
+const MAX_SLEEP_MS: i64 = 60_000;
+
+#[derive(Debug, Clone)]
+pub struct ScheduleSettings {
+    pub name: String,
+    pub plugin_id: String,
+    pub cron: Option<String>,
+    pub interval_seconds: Option<i64>,
+    pub timezone: String,
+    pub params: HashMap<String, Value>,
+    pub enabled: bool,
+    pub overlap_policy: OverlapPolicy,
+}
+
+// 服务停止期间错过的运行在启动时补跑一次
+#[derive(Clone)]
+pub struct SchedulerService {
+    schedule_repo: ScheduleRepository,
+    executions: ExecutionService,
+    wake: Arc<Notify>,
+    // 等待上一次执行结束的计划
+    queued: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SchedulerService {
+    pub fn new(schedule_repo: ScheduleRepository, executions: ExecutionService) -> Self {
+        Self {
+            schedule_repo,
+            executions,
+            wake: Arc::new(Notify::new()),
+            queued: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    pub async fn list_schedules(&self) -> Result<Vec<Schedule>> {
+        self.schedule_repo.list().await
+    }
+
+    pub async fn get_schedule(&self, id: &str) -> Result<Schedule> {
+        self.schedule_repo.get(id).await
+    }
+
+    pub async fn create_schedule(&self, settings: ScheduleSettings) -> Result<Schedule> {
+        let now = Utc::now();
+        let next_run_at = self.validate(&settings, now).await?;
+        let schedule = Schedule {
+            id: Uuid::new_v4().to_string(),
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            cron: settings.cron,
+            interval_seconds: settings.interval_seconds,
+            timezone: settings.timezone,
+            params: Self::serialize_params(&settings.params)?,
+            enabled: settings.enabled,
+            overlap_policy: settings.overlap_policy,
+            last_run_at: None,
+            last_execution_id: None,
+            last_error: None,
+            next_run_at,
+            created_at: now.timestamp_millis(),
+            updated_at: now.timestamp_millis(),
+        };
+        self.schedule_repo.create(&schedule).await?;
+        self.wake.notify_one();
+        Ok(schedule)
+    }
+
+    pub async fn update_schedule(&self, id: &str, settings: ScheduleSettings) -> Result<Schedule> {
+        let existing = self.schedule_repo.get(id).await?;
+        let now = Utc::now();
+        let next_run_at = self.validate(&settings, now).await?;
+        let schedule = Schedule {
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            cron: settings.cron,
+            interval_seconds: settings.interval_seconds,
+            timezone: settings.timezone,
+            params: Self::serialize_params(&settings.params)?,
+            enabled: settings.enabled,
+            overlap_policy: settings.overlap_policy,
+            next_run_at,
+            updated_at: now.timestamp_millis(),
+            ..existing
+        };
+        self.schedule_repo.update(&schedule).await?;
+        self.wake.notify_one();
+        Ok(schedule)
+    }
+
+    pub async fn delete_schedule(&self, id: &str) -> Result<()> {
+        self.schedule_repo.delete(id).await?;
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    pub fn spawn_ticker(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let now = Utc::now();
+                if let Err(err) = service.fire_due(now).await {
+                    tracing::error!("Failed to run due schedules: {}", err);
+                }
+
+                let wait_ms = match service.schedule_repo.next_run_at().await {
+                    Ok(Some(next)) => (next - Utc::now().timestamp_millis()).clamp(0, MAX_SLEEP_MS),
+                    Ok(None) => MAX_SLEEP_MS,
+                    Err(err) => {
+                        tracing::error!("Failed to read next schedule run: {}", err);
+                        MAX_SLEEP_MS
+                    }
+                };
+                tokio::select! {
+                    _ = sleep(Duration::from_millis(wait_ms as u64)) => {}
+                    _ = service.wake.notified() => {}
+                }
+            }
+        });
+    }
+
+    async fn fire_due(&self, now: DateTime<Utc>) -> Result<()> {
+        for schedule in self.schedule_repo.list_due(now.timestamp_millis()).await? {
+            let Some(due_at) = schedule.next_run_at else {
+                continue;
+            };
+            let next_run_at = match Self::next_run_after(
+                schedule.cron.as_deref(),
+                schedule.interval_seconds,
+                &schedule.timezone,
+                due_at,
+                now,
+            ) {
+                Ok(next) => next,
+                Err(err) => {
+                    tracing::error!("Schedule {} has invalid timing: {}", schedule.id, err);
+                    None
+                }
+            };
+            // 并发修改（更新或删除）时放弃本次触发，按新设置重新计算
+            if !self
+                .schedule_repo
+                .advance(&schedule.id, due_at, next_run_at)
+                .await?
+            {
+                continue;
+            }
+            self.run_due(schedule).await?;
+        }
+        Ok(())
+    }
+
+    async fn run_due(&self, schedule: Schedule) -> Result<()> {
+        let previous = match &schedule.last_execution_id {
+            Some(id) if schedule.overlap_policy != OverlapPolicy::Allow => {
+                self.active_execution(id).await?
+            }
+            _ => None,
+        };
+        let Some(previous) = previous else {
+            self.start_execution(&schedule).await;
+            return Ok(());
+        };
+
+        match schedule.overlap_policy {
+            OverlapPolicy::Queue => {
+                if !self.queued.lock().unwrap().insert(schedule.id.clone()) {
+                    tracing::info!(
+                        "Schedule {} already has a run queued, dropping this one",
+                        schedule.id
+                    );
+                    return Ok(());
+                }
+                let service = self.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = service.wait_for_finish(&previous).await {
+                        tracing::warn!(
+                            "Failed to wait for execution {} of schedule {}: {}",
+                            previous,
+                            schedule.id,
+                            err
+                        );
+                    }
+                    service.queued.lock().unwrap().remove(&schedule.id);
+                    // 排队期间可能被删除或停用
+                    match service.schedule_repo.get(&schedule.id).await {
+                        Ok(current) if current.enabled => service.start_execution(&current).await,
+                        Ok(_) | Err(AppError::ScheduleNotFound(_)) => {}
+                        Err(err) => {
+                            tracing::error!("Failed to load schedule {}: {}", schedule.id, err)
+                        }
+                    }
+                });
+            }
+            _ => {
+                let reason = format!("Skipped: execution {} was still running", previous);
+                tracing::info!("Schedule {}: {}", schedule.id, reason);
+                self.schedule_repo
+                    .record_skip(&schedule.id, &reason)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn start_execution(&self, schedule: &Schedule) {
+        let started = match serde_json::from_str::<HashMap<String, Value>>(&schedule.params) {
+            Ok(params) => {
+                self.executions
+                    .execute_triggered(
+                        &schedule.plugin_id,
+                        params,
+                        format!("schedule:{}", schedule.id),
+                    )
+                    .await
+            }
+            Err(e) => Err(AppError::Schedule(format!(
+                "Invalid schedule params: {}",
+                e
+            ))),
+        };
+        let recorded = match started {
+            Ok(execution) => {
+                tracing::info!(
+                    "Schedule {} started execution {}",
+                    schedule.id,
+                    execution.id
+                );
+                self.schedule_repo
+                    .record_run(&schedule.id, Some(&execution.id), None)
+                    .await
+            }
+            Err(err) => {
+                tracing::warn!("Schedule {} failed to start: {}", schedule.id, err);
+                self.schedule_repo
+                    .record_run(&schedule.id, None, Some(err.to_string()))
+                    .await
+            }
+        };
+        if let Err(err) = recorded {
+            tracing::error!("Failed to record run of schedule {}: {}", schedule.id, err);
+        }
+    }
+
+    async fn active_execution(&self, execution_id: &str) -> Result<Option<String>> {
+        match self.executions.get_execution(execution_id).await {
+            Ok(execution) if !FINISHED_STATUSES.contains(&execution.status) => {
+                Ok(Some(execution.id))
+            }
+            Ok(_) | Err(AppError::ExecutionNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn wait_for_finish(&self, execution_id: &str) -> Result<()> {
+        loop {
+            let execution = self
+                .executions
+                .wait_for_states(execution_id, &FINISHED_STATUSES, WAIT_INTERVAL_MS)
+                .await?;
+            if FINISHED_STATUSES.contains(&execution.status) {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn validate(
+        &self,
+        settings: &ScheduleSettings,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>> {
+        if settings.name.trim().is_empty() {
+            return Err(AppError::Schedule(
+                "Schedule name cannot be empty".to_string(),
+            ));
+        }
+        let next_run_at = Self::next_run_after(
+            settings.cron.as_deref(),
+            settings.interval_seconds,
+            &settings.timezone,
+            now.timestamp_millis(),
+            now,
+        )?;
+        if next_run_at.is_none() {
+            return Err(AppError::Schedule(
+                "Cron expression never fires".to_string(),
+            ));
+        }
+
+        self.executions
+            .check_parameters(&settings.plugin_id, settings.params.clone())
+            .await?;
+        Ok(next_run_at.filter(|_| settings.enabled))
+    }
+
+    // 间隔计划没有错过运行时保持原有节奏
+    fn next_run_after(
+        cron: Option<&str>,
+        interval_seconds: Option<i64>,
+        timezone: &str,
+        due_at: i64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>> {
+        let timezone = Tz::from_str(timezone)
+            .map_err(|_| AppError::Schedule(format!("Unknown time zone '{}'", timezone)))?;
+        match (cron, interval_seconds) {
+            (Some(expression), None) => {
+                let cron = Self::parse_cron(expression)?;
+                Ok(cron
+                    .after(&now.with_timezone(&timezone))
+                    .next()
+                    .map(|next| next.timestamp_millis()))
+            }
+            (None, Some(seconds)) if seconds >= 1 => {
+                let interval = seconds * 1000;
+                let next = due_at + interval;
+                let now = now.timestamp_millis();
+                Ok(Some(if next > now { next } else { now + interval }))
+            }
+            (None, Some(_)) => Err(AppError::Schedule(
+                "interval_seconds must be at least 1".to_string(),
+            )),
+            _ => Err(AppError::Schedule(
+                "Set exactly one of cron and interval_seconds".to_string(),
+            )),
+        }
+    }
+
+    // 接受常见的五段 crontab（周日为 0 或 7），以及 cron crate 带秒（和年）的六、七段格式（周日为 1）
+    fn parse_cron(expression: &str) -> Result<cron::Schedule> {
+        let expression = expression.trim();
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let expanded = if let [minute, hour, day, month, weekday] = fields.as_slice() {
+            format!(
+                "0 {} {} {} {} {}",
+                minute,
+                hour,
+                day,
+                month,
+                Self::crontab_weekdays(weekday)
+            )
+        } else {
+            expression.to_string()
+        };
+        cron::Schedule::from_str(&expanded).map_err(|e| {
+            AppError::Schedule(format!("Invalid cron expression '{}': {}", expression, e))
+        })
+    }
+
+    // 把 crontab 的星期（周日为 0 或 7）换算成 cron crate 的编号（周日为 1），MON-FRI 等名称保持不变
+    fn crontab_weekdays(field: &str) -> String {
+        let day = |value: &str| value.parse::<u32>().ok().filter(|day| *day <= 7);
+        let shift = |value: &str| match day(value) {
+            Some(day) => (day % 7 + 1).to_string(),
+            None => value.to_string(),
+        };
+        field
+            .split(',')
+            .map(|item| {
+                let (range, step) = match item.split_once('/') {
+                    Some((range, step)) => (range, Some(step)),
+                    None => (item, None),
+                };
+                // 数字区间先按 crontab 的含义展开再换算，跨过周六的区间（如 5-7、0-7）不会变成倒序区间
+                let bounds = match range.split_once('-') {
+                    Some((start, end)) => day(start).zip(day(end)),
+                    None if step.is_some() => day(range).map(|start| (start, 7)),
+                    None => None,
+                };
+                let step = match step {
+                    Some(step) => step.parse::<usize>().ok().filter(|step| *step > 0),
+                    None => Some(1),
+                };
+                if let (Some((start, end)), Some(step)) = (bounds, step)
+                    && start <= end
+                {
+                    let mut days: Vec<u32> =
+                        (start..=end).step_by(step).map(|day| day % 7 + 1).collect();
+                    days.sort_unstable();
+                    days.dedup();
+                    return Self::join_days(&days);
+                }
+
+                // 名称、* 和无效的值只换算其中的数字，交给 cron crate 校验
+                let mut renumbered = match range.split_once('-') {
+                    Some((start, end)) => format!("{}-{}", shift(start), shift(end)),
+                    None => shift(range),
+                };
+                if let Some((_, step)) = item.split_once('/') {
+                    renumbered = format!("{}/{}", renumbered, step);
+                }
+                renumbered
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    // 连续的日期合并成区间，例如 [1, 6, 7] => 1,6-7
+    fn join_days(days: &[u32]) -> String {
+        let mut parts = Vec::new();
+        let mut index = 0;
+        while index < days.len() {
+            let mut end = index;
+            while end + 1 < days.len() && days[end + 1] == days[end] + 1 {
+                end += 1;
+            }
+            parts.push(if end == index {
+                days[index].to_string()
+            } else {
+                format!("{}-{}", days[index], days[end])
+            });
+            index = end + 1;
+        }
+        parts.join(",")
+    }
+
+    fn serialize_params(params: &HashMap<String, Value>) -> Result<String> {
+        serde_json::to_string(params)
+            .map_err(|e| AppError::Schedule(format!("Failed to serialize params: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Weekday};
+
+    fn weekdays(expression: &str, count: usize) -> Vec<Weekday> {
+        // 2024-01-04 是周四
+        let start = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+        SchedulerService::parse_cron(expression)
+            .unwrap()
+            .after(&start)
+            .take(count)
+            .map(|time| time.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn renumbers_crontab_weekdays() {
+        assert_eq!(SchedulerService::crontab_weekdays("0"), "1");
+        assert_eq!(SchedulerService::crontab_weekdays("7"), "1");
+        assert_eq!(SchedulerService::crontab_weekdays("1-5"), "2-6");
+        assert_eq!(SchedulerService::crontab_weekdays("5-7"), "1,6-7");
+        assert_eq!(SchedulerService::crontab_weekdays("0-6"), "1-7");
+        assert_eq!(SchedulerService::crontab_weekdays("0-7"), "1-7");
+        assert_eq!(SchedulerService::crontab_weekdays("*/2"), "*/2");
+        assert_eq!(SchedulerService::crontab_weekdays("1-5/2"), "2,4,6");
+        assert_eq!(SchedulerService::crontab_weekdays("5-7/2"), "1,6");
+        assert_eq!(SchedulerService::crontab_weekdays("0-7/3"), "1,4,7");
+        assert_eq!(SchedulerService::crontab_weekdays("3/2"), "1,4,6");
+        assert_eq!(SchedulerService::crontab_weekdays("5-1"), "6-2");
+        assert_eq!(SchedulerService::crontab_weekdays("8"), "8");
+        assert_eq!(SchedulerService::crontab_weekdays("MON-FRI"), "MON-FRI");
+        assert_eq!(SchedulerService::crontab_weekdays("0,3"), "1,4");
+    }
+
+    #[test]
+    fn five_field_crontab_uses_crontab_weekdays() {
+        use Weekday::*;
+        assert_eq!(weekdays("0 9 * * 5-7", 4), vec![Fri, Sat, Sun, Fri]);
+        assert_eq!(weekdays("0 9 * * 0", 2), vec![Sun, Sun]);
+        assert_eq!(weekdays("0 9 * * */2", 4), vec![Thu, Sat, Sun, Tue]);
+        assert_eq!(weekdays("0 9 * * MON-FRI", 3), vec![Thu, Fri, Mon]);
+        assert_eq!(
+            weekdays("0 9 * * 0-7", 7),
+            vec![Thu, Fri, Sat, Sun, Mon, Tue, Wed]
+        );
+        assert_eq!(weekdays("0 9 * * 5-7/2", 3), vec![Fri, Sun, Fri]);
+        assert_eq!(weekdays("0 9 * * 1-5/2", 3), vec![Fri, Mon, Wed]);
+        assert!(SchedulerService::parse_cron("0 9 * * 5-1").is_err());
+        assert!(SchedulerService::parse_cron("0 9 * * 8").is_err());
+    }
+
+    #[test]
+    fn six_field_expressions_are_passed_through() {
+        // cron crate 的编号中 1 是周日
+        assert_eq!(weekdays("0 0 9 * * 1", 2), vec![Weekday::Sun, Weekday::Sun]);
+        assert!(SchedulerService::parse_cron("not a cron").is_err());
+    }
+
+    #[test]
+    fn cron_runs_in_the_schedule_time_zone() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+        let next =
+            SchedulerService::next_run_after(Some("0 9 * * *"), None, "Asia/Shanghai", 0, now)
+                .unwrap();
+        let expected = Utc.with_ymd_and_hms(2024, 1, 4, 1, 0, 0).unwrap();
+        assert_eq!(next, Some(expected.timestamp_millis()));
+        assert!(
+            SchedulerService::next_run_after(Some("0 9 * * *"), None, "Mars/Olympus", 0, now)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn interval_keeps_its_cadence_unless_runs_were_missed() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+        let now_ms = now.timestamp_millis();
+        let next = |due_at| SchedulerService::next_run_after(None, Some(60), "UTC", due_at, now);
+        assert_eq!(next(now_ms - 10_000).unwrap(), Some(now_ms + 50_000));
+        assert_eq!(next(now_ms - 600_000).unwrap(), Some(now_ms + 60_000));
+        assert!(SchedulerService::next_run_after(None, Some(0), "UTC", now_ms, now).is_err());
+        assert!(
+            SchedulerService::next_run_after(Some("0 9 * * *"), Some(60), "UTC", now_ms, now)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::models::ExecutionStatus;
+use tokio::sync::broadcast;
+
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    pub execution_id: String,
+    pub status: ExecutionStatus,
+}
+
+// 写入数据库之后才广播状态变化
+#[derive(Clone)]
+pub struct StatusWatch {
+    sender: broadcast::Sender<StatusChange>,
+}
+
+impl Default for StatusWatch {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl StatusWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(&self, execution_id: &str, status: ExecutionStatus) {
+        // 没有等待者时发送失败，忽略即可
+        let _ = self.sender.send(StatusChange {
+            execution_id: execution_id.to_string(),
+            status,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StatusChange> {
+        self.sender.subscribe()
+    }
+}
@@ -29,6 +29,12 @@ pub struct UpdateStatus {
 #[derive(Clone)]
 pub struct UpdateService;
 
+impl Default for UpdateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UpdateService {
     pub fn new() -> Self {
         Self
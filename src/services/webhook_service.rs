@@ -0,0 +1,158 @@
+use crate::error::{AppError, Result};
+use crate::models::{Execution, Webhook};
+use crate::repository::WebhookRepository;
+use crate::services::execution_service::ExecutionService;
+use crate::services::json_path::JsonPath;
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct WebhookSettings {
+    pub name: String,
+    pub plugin_id: String,
+    pub mapping: HashMap<String, String>,
+    pub wait_for_result: bool,
+    pub enabled: bool,
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    webhook_repo: WebhookRepository,
+    executions: ExecutionService,
+}
+
+impl WebhookService {
+    pub fn new(webhook_repo: WebhookRepository, executions: ExecutionService) -> Self {
+        Self {
+            webhook_repo,
+            executions,
+        }
+    }
+
+    pub async fn list_webhooks(&self) -> Result<Vec<Webhook>> {
+        self.webhook_repo.list().await
+    }
+
+    pub async fn get_webhook(&self, id: &str) -> Result<Webhook> {
+        self.webhook_repo.get(id).await
+    }
+
+    pub async fn create_webhook(&self, settings: WebhookSettings) -> Result<Webhook> {
+        self.validate(&settings).await?;
+        let now = Utc::now().timestamp_millis();
+        let webhook = Webhook {
+            id: Uuid::new_v4().to_string(),
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            token: Self::new_token(),
+            mapping: Self::serialize_mapping(&settings.mapping)?,
+            wait_for_result: settings.wait_for_result,
+            enabled: settings.enabled,
+            last_triggered_at: None,
+            last_execution_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.webhook_repo.create(&webhook).await?;
+        Ok(webhook)
+    }
+
+    pub async fn update_webhook(&self, id: &str, settings: WebhookSettings) -> Result<Webhook> {
+        let existing = self.webhook_repo.get(id).await?;
+        self.validate(&settings).await?;
+        let webhook = Webhook {
+            name: settings.name,
+            plugin_id: settings.plugin_id,
+            mapping: Self::serialize_mapping(&settings.mapping)?,
+            wait_for_result: settings.wait_for_result,
+            enabled: settings.enabled,
+            updated_at: Utc::now().timestamp_millis(),
+            ..existing
+        };
+        self.webhook_repo.update(&webhook).await?;
+        Ok(webhook)
+    }
+
+    pub async fn delete_webhook(&self, id: &str) -> Result<()> {
+        self.webhook_repo.delete(id).await
+    }
+
+    pub async fn rotate_token(&self, id: &str) -> Result<Webhook> {
+        let webhook = Webhook {
+            token: Self::new_token(),
+            updated_at: Utc::now().timestamp_millis(),
+            ..self.webhook_repo.get(id).await?
+        };
+        self.webhook_repo.update(&webhook).await?;
+        Ok(webhook)
+    }
+
+    // 路径不存在或为 null 时参数使用默认值
+    pub async fn trigger(&self, token: &str, body: &Value) -> Result<(Webhook, Execution)> {
+        let webhook = self.webhook_repo.get_by_token(token).await?;
+        if !webhook.enabled {
+            return Err(AppError::WebhookDisabled);
+        }
+
+        let mut params = HashMap::new();
+        for (name, path) in Self::parse_mapping(&webhook.mapping)? {
+            match JsonPath::parse(&path)?.select(body) {
+                None | Some(Value::Null) => {}
+                Some(value) => {
+                    params.insert(name, value.clone());
+                }
+            }
+        }
+
+        let execution = self
+            .executions
+            .execute_triggered(
+                &webhook.plugin_id,
+                params,
+                format!("webhook:{}", webhook.id),
+            )
+            .await?;
+        self.webhook_repo
+            .record_trigger(&webhook.id, &execution.id)
+            .await?;
+        Ok((webhook, execution))
+    }
+
+    async fn validate(&self, settings: &WebhookSettings) -> Result<()> {
+        if settings.name.trim().is_empty() {
+            return Err(AppError::Webhook(
+                "Webhook name cannot be empty".to_string(),
+            ));
+        }
+        let declared = self
+            .executions
+            .plugin_parameters(&settings.plugin_id)
+            .await?;
+        for (name, path) in &settings.mapping {
+            if !declared.iter().any(|param| &param.name == name) {
+                return Err(AppError::Webhook(format!(
+                    "Plugin '{}' has no parameter '{}'",
+                    settings.plugin_id, name
+                )));
+            }
+            JsonPath::parse(path)?;
+        }
+        Ok(())
+    }
+
+    fn new_token() -> String {
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    fn parse_mapping(raw: &str) -> Result<HashMap<String, String>> {
+        serde_json::from_str(raw)
+            .map_err(|e| AppError::Webhook(format!("Invalid webhook mapping: {}", e)))
+    }
+
+    fn serialize_mapping(mapping: &HashMap<String, String>) -> Result<String> {
+        serde_json::to_string(mapping)
+            .map_err(|e| AppError::Webhook(format!("Failed to serialize webhook mapping: {}", e)))
+    }
+}
@@ -0,0 +1,558 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    Execution, ExecutionStatus, FailurePolicy, RunCondition, StepRunStatus, Workflow, WorkflowRun,
+    WorkflowRunStatus, WorkflowStep, WorkflowStepRun,
+};
+use crate::repository::{PluginRepository, WorkflowRepository};
+use crate::services::execution_service::ExecutionService;
+use crate::services::workflow_template;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+const FINISHED_STATUSES: [ExecutionStatus; 4] = [
+    ExecutionStatus::Completed,
+    ExecutionStatus::Failed,
+    ExecutionStatus::Stopped,
+    ExecutionStatus::TimedOut,
+];
+const WAIT_INTERVAL_MS: u64 = 60_000;
+const INTERRUPTED_REASON: &str = "Workflow run interrupted by restart";
+
+pub type WorkflowRunDetail = (WorkflowRun, Vec<WorkflowStepRun>);
+
+#[derive(Clone)]
+pub struct WorkflowService {
+    workflow_repo: WorkflowRepository,
+    plugin_repo: PluginRepository,
+    executions: ExecutionService,
+}
+
+impl WorkflowService {
+    pub fn new(
+        workflow_repo: WorkflowRepository,
+        plugin_repo: PluginRepository,
+        executions: ExecutionService,
+    ) -> Self {
+        Self {
+            workflow_repo,
+            plugin_repo,
+            executions,
+        }
+    }
+
+    pub async fn list_workflows(&self) -> Result<Vec<Workflow>> {
+        self.workflow_repo.list().await
+    }
+
+    pub async fn get_workflow(&self, id: &str) -> Result<Workflow> {
+        self.workflow_repo.get(id).await
+    }
+
+    pub async fn create_workflow(
+        &self,
+        name: String,
+        description: Option<String>,
+        steps: Vec<WorkflowStep>,
+        failure_policy: FailurePolicy,
+    ) -> Result<Workflow> {
+        self.validate(&name, &steps).await?;
+        let now = Utc::now().timestamp_millis();
+        let workflow = Workflow {
+            id: Uuid::new_v4().to_string(),
+            name,
+            description,
+            steps: Self::serialize_steps(&steps)?,
+            failure_policy,
+            created_at: now,
+            updated_at: now,
+        };
+        self.workflow_repo.create(&workflow).await?;
+        Ok(workflow)
+    }
+
+    // 正在进行的运行继续使用开始时的步骤
+    pub async fn update_workflow(
+        &self,
+        id: &str,
+        name: String,
+        description: Option<String>,
+        steps: Vec<WorkflowStep>,
+        failure_policy: FailurePolicy,
+    ) -> Result<Workflow> {
+        let existing = self.workflow_repo.get(id).await?;
+        self.validate(&name, &steps).await?;
+        let workflow = Workflow {
+            name,
+            description,
+            steps: Self::serialize_steps(&steps)?,
+            failure_policy,
+            updated_at: Utc::now().timestamp_millis(),
+            ..existing
+        };
+        self.workflow_repo.update(&workflow).await?;
+        Ok(workflow)
+    }
+
+    pub async fn delete_workflow(&self, id: &str) -> Result<()> {
+        let runs = self.workflow_repo.list_runs(id).await?;
+        if runs
+            .iter()
+            .any(|run| run.status == WorkflowRunStatus::Running)
+        {
+            return Err(AppError::Workflow(
+                "Workflow has a run in progress, stop it first".to_string(),
+            ));
+        }
+        self.workflow_repo.delete(id).await
+    }
+
+    pub async fn start_run(&self, workflow_id: &str) -> Result<WorkflowRunDetail> {
+        let workflow = self.workflow_repo.get(workflow_id).await?;
+        let steps = Self::parse_steps(&workflow)?;
+        let run = WorkflowRun {
+            id: Uuid::new_v4().to_string(),
+            workflow_id: workflow.id.clone(),
+            status: WorkflowRunStatus::Running,
+            error_message: None,
+            started_at: Utc::now().timestamp_millis(),
+            finished_at: None,
+        };
+        let step_ids: Vec<String> = steps.iter().map(|step| step.id.clone()).collect();
+        self.workflow_repo.create_run(&run, &step_ids).await?;
+
+        let service = self.clone();
+        let run_id = run.id.clone();
+        tokio::spawn(async move {
+            service
+                .drive_run(run_id, workflow.failure_policy, steps)
+                .await;
+        });
+        self.get_run(&run.id).await
+    }
+
+    pub async fn get_run(&self, run_id: &str) -> Result<WorkflowRunDetail> {
+        let run = self.workflow_repo.get_run(run_id).await?;
+        let steps = self.workflow_repo.list_step_runs(run_id).await?;
+        Ok((run, steps))
+    }
+
+    pub async fn list_runs(&self, workflow_id: &str) -> Result<Vec<WorkflowRunDetail>> {
+        self.workflow_repo.get(workflow_id).await?;
+        let mut runs = Vec::new();
+        for run in self.workflow_repo.list_runs(workflow_id).await? {
+            let steps = self.workflow_repo.list_step_runs(&run.id).await?;
+            runs.push((run, steps));
+        }
+        Ok(runs)
+    }
+
+    pub async fn stop_run(&self, run_id: &str) -> Result<WorkflowRunDetail> {
+        if !self
+            .workflow_repo
+            .finish_run(run_id, WorkflowRunStatus::Stopped, None)
+            .await?
+        {
+            self.workflow_repo.get_run(run_id).await?;
+            return Err(AppError::Workflow(
+                "Workflow run is not running".to_string(),
+            ));
+        }
+
+        for step in self.workflow_repo.list_step_runs(run_id).await? {
+            if step.status != StepRunStatus::Running {
+                continue;
+            }
+            if let Some(execution_id) = step.execution_id
+                && let Err(err) = self.executions.stop_execution(&execution_id).await
+            {
+                tracing::warn!(
+                    "Failed to stop execution {} of workflow run {}: {}",
+                    execution_id,
+                    run_id,
+                    err
+                );
+            }
+        }
+        self.get_run(run_id).await
+    }
+
+    // 步骤的执行由 ExecutionService::recover_orphans 处理
+    pub async fn recover_interrupted_runs(&self) -> Result<()> {
+        let runs = self
+            .workflow_repo
+            .list_runs_by_status(WorkflowRunStatus::Running)
+            .await?;
+        for run in runs {
+            tracing::warn!("Workflow run {} was interrupted by restart", run.id);
+            self.workflow_repo
+                .close_steps(&run.id, INTERRUPTED_REASON)
+                .await?;
+            self.workflow_repo
+                .finish_run(
+                    &run.id,
+                    WorkflowRunStatus::Failed,
+                    Some(INTERRUPTED_REASON.to_string()),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn drive_run(&self, run_id: String, policy: FailurePolicy, steps: Vec<WorkflowStep>) {
+        let (status, error_message) = match self.run_steps(&run_id, policy, &steps).await {
+            Ok(true) => (WorkflowRunStatus::Completed, None),
+            Ok(false) => (
+                WorkflowRunStatus::Failed,
+                Some("One or more steps failed".to_string()),
+            ),
+            Err(err) => {
+                tracing::error!("Workflow run {} failed: {}", run_id, err);
+                (WorkflowRunStatus::Failed, Some(err.to_string()))
+            }
+        };
+
+        // 停止的运行已记录为 Stopped，finish_run 不会覆盖
+        let finished = async {
+            self.workflow_repo
+                .close_steps(&run_id, "Workflow run ended")
+                .await?;
+            self.workflow_repo
+                .finish_run(&run_id, status, error_message)
+                .await
+        };
+        if let Err(err) = finished.await {
+            tracing::error!("Failed to record end of workflow run {}: {}", run_id, err);
+        }
+    }
+
+    // 返回是否没有步骤失败
+    async fn run_steps(
+        &self,
+        run_id: &str,
+        policy: FailurePolicy,
+        steps: &[WorkflowStep],
+    ) -> Result<bool> {
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel::<(String, Result<Execution>)>();
+        let mut statuses: HashMap<String, StepRunStatus> = steps
+            .iter()
+            .map(|step| (step.id.clone(), StepRunStatus::Pending))
+            .collect();
+        let mut executions: HashMap<String, Execution> = HashMap::new();
+        let mut running = 0;
+        let mut failed = false;
+        let mut stopped = false;
+
+        loop {
+            let mut progressed = !stopped;
+            while progressed {
+                progressed = false;
+                for step in steps {
+                    if statuses[&step.id] != StepRunStatus::Pending {
+                        continue;
+                    }
+                    let upstream: Vec<StepRunStatus> = step
+                        .depends_on
+                        .iter()
+                        .map(|dependency| statuses[dependency])
+                        .collect();
+                    if upstream.iter().any(|status| {
+                        matches!(status, StepRunStatus::Pending | StepRunStatus::Running)
+                    }) {
+                        continue;
+                    }
+                    progressed = true;
+
+                    let halted = failed
+                        && policy == FailurePolicy::Stop
+                        && step.run_if == RunCondition::Success;
+                    if halted || !Self::condition_met(step.run_if, &upstream) {
+                        self.workflow_repo
+                            .finish_step(run_id, &step.id, StepRunStatus::Skipped, None)
+                            .await?;
+                        statuses.insert(step.id.clone(), StepRunStatus::Skipped);
+                        continue;
+                    }
+
+                    match self.start_step(run_id, step, &executions).await {
+                        Ok(execution_id) => {
+                            statuses.insert(step.id.clone(), StepRunStatus::Running);
+                            running += 1;
+                            let service = self.clone();
+                            let done_tx = done_tx.clone();
+                            let step_id = step.id.clone();
+                            tokio::spawn(async move {
+                                let outcome = service.wait_for_finish(&execution_id).await;
+                                let _ = done_tx.send((step_id, outcome));
+                            });
+                        }
+                        Err(err) => {
+                            self.workflow_repo
+                                .finish_step(
+                                    run_id,
+                                    &step.id,
+                                    StepRunStatus::Failed,
+                                    Some(err.to_string()),
+                                )
+                                .await?;
+                            statuses.insert(step.id.clone(), StepRunStatus::Failed);
+                            failed = true;
+                        }
+                    }
+                }
+            }
+
+            if running == 0 {
+                break;
+            }
+            let Some((step_id, outcome)) = done_rx.recv().await else {
+                break;
+            };
+            running -= 1;
+
+            let (status, error_message) = match outcome {
+                Ok(execution) => {
+                    let result = if execution.status == ExecutionStatus::Completed {
+                        (StepRunStatus::Completed, None)
+                    } else {
+                        (
+                            StepRunStatus::Failed,
+                            Some(format!(
+                                "Execution ended with status {:?}",
+                                execution.status
+                            )),
+                        )
+                    };
+                    executions.insert(step_id.clone(), execution);
+                    result
+                }
+                Err(err) => (StepRunStatus::Failed, Some(err.to_string())),
+            };
+            self.workflow_repo
+                .finish_step(run_id, &step_id, status, error_message)
+                .await?;
+            statuses.insert(step_id, status);
+            failed |= status == StepRunStatus::Failed;
+
+            if self.workflow_repo.get_run(run_id).await?.status == WorkflowRunStatus::Stopped {
+                stopped = true;
+            }
+        }
+
+        Ok(!failed)
+    }
+
+    async fn start_step(
+        &self,
+        run_id: &str,
+        step: &WorkflowStep,
+        executions: &HashMap<String, Execution>,
+    ) -> Result<String> {
+        let mut params = HashMap::new();
+        for (name, value) in &step.params {
+            params.insert(name.clone(), workflow_template::render(value, executions)?);
+        }
+        let execution = self
+            .executions
+            .execute_triggered(&step.plugin_id, params, format!("workflow:{}", run_id))
+            .await?;
+        self.workflow_repo
+            .start_step(run_id, &step.id, &execution.id)
+            .await?;
+        Ok(execution.id)
+    }
+
+    async fn wait_for_finish(&self, execution_id: &str) -> Result<Execution> {
+        loop {
+            let execution = self
+                .executions
+                .wait_for_states(execution_id, &FINISHED_STATUSES, WAIT_INTERVAL_MS)
+                .await?;
+            if FINISHED_STATUSES.contains(&execution.status) {
+                return Ok(execution);
+            }
+        }
+    }
+
+    fn condition_met(condition: RunCondition, upstream: &[StepRunStatus]) -> bool {
+        match condition {
+            RunCondition::Success => upstream
+                .iter()
+                .all(|status| *status == StepRunStatus::Completed),
+            RunCondition::Failure => upstream.contains(&StepRunStatus::Failed),
+            RunCondition::Always => true,
+        }
+    }
+
+    async fn validate(&self, name: &str, steps: &[WorkflowStep]) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(AppError::Workflow(
+                "Workflow name cannot be empty".to_string(),
+            ));
+        }
+        if steps.is_empty() {
+            return Err(AppError::Workflow(
+                "Workflow needs at least one step".to_string(),
+            ));
+        }
+
+        let mut ids = HashSet::new();
+        for step in steps {
+            let valid_id = !step.id.is_empty()
+                && step
+                    .id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_id {
+                return Err(AppError::Workflow(format!(
+                    "Invalid step id '{}': use letters, digits, '-' and '_'",
+                    step.id
+                )));
+            }
+            if !ids.insert(step.id.as_str()) {
+                return Err(AppError::Workflow(format!(
+                    "Duplicate step id: {}",
+                    step.id
+                )));
+            }
+        }
+
+        for step in steps {
+            for dependency in &step.depends_on {
+                if dependency == &step.id || !ids.contains(dependency.as_str()) {
+                    return Err(AppError::Workflow(format!(
+                        "Step '{}' depends on unknown step '{}'",
+                        step.id, dependency
+                    )));
+                }
+            }
+            self.plugin_repo.get(&step.plugin_id).await?;
+        }
+
+        let ancestors = Self::ancestors(steps)?;
+        for step in steps {
+            for value in step.params.values() {
+                for referenced in workflow_template::referenced_steps(value)? {
+                    if !ancestors[&step.id].contains(&referenced) {
+                        return Err(AppError::Workflow(format!(
+                            "Step '{}' refers to '{}', which is not upstream of it",
+                            step.id, referenced
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn ancestors(steps: &[WorkflowStep]) -> Result<HashMap<String, HashSet<String>>> {
+        let mut remaining: HashMap<&str, usize> = steps
+            .iter()
+            .map(|step| (step.id.as_str(), step.depends_on.len()))
+            .collect();
+        let mut ready: VecDeque<&WorkflowStep> = steps
+            .iter()
+            .filter(|step| step.depends_on.is_empty())
+            .collect();
+
+        let mut ancestors: HashMap<String, HashSet<String>> = HashMap::new();
+        while let Some(step) = ready.pop_front() {
+            let mut set = HashSet::new();
+            for dependency in &step.depends_on {
+                set.insert(dependency.clone());
+                set.extend(ancestors[dependency].iter().cloned());
+            }
+            ancestors.insert(step.id.clone(), set);
+
+            for dependent in steps.iter().filter(|s| s.depends_on.contains(&step.id)) {
+                let count = remaining.entry(dependent.id.as_str()).or_default();
+                *count -= dependent
+                    .depends_on
+                    .iter()
+                    .filter(|dependency| *dependency == &step.id)
+                    .count();
+                if *count == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+
+        if ancestors.len() != steps.len() {
+            return Err(AppError::Workflow(
+                "Workflow steps form a cycle".to_string(),
+            ));
+        }
+        Ok(ancestors)
+    }
+
+    fn parse_steps(workflow: &Workflow) -> Result<Vec<WorkflowStep>> {
+        serde_json::from_str(&workflow.steps)
+            .map_err(|e| AppError::Workflow(format!("Invalid workflow steps: {}", e)))
+    }
+
+    fn serialize_steps(steps: &[WorkflowStep]) -> Result<String> {
+        serde_json::to_string(steps)
+            .map_err(|e| AppError::Workflow(format!("Failed to serialize workflow steps: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, depends_on: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            id: id.to_string(),
+            plugin_id: "plugin".to_string(),
+            params: HashMap::new(),
+            depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+            run_if: RunCondition::Success,
+        }
+    }
+
+    fn set(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn ancestors_include_indirect_dependencies() {
+        let steps = vec![
+            step("deploy", &["test", "lint"]),
+            step("build", &[]),
+            step("test", &["build"]),
+            step("lint", &["build"]),
+            step("notify", &[]),
+        ];
+        let ancestors = WorkflowService::ancestors(&steps).unwrap();
+        assert_eq!(ancestors["build"], set(&[]));
+        assert_eq!(ancestors["test"], set(&["build"]));
+        assert_eq!(ancestors["deploy"], set(&["build", "test", "lint"]));
+        assert_eq!(ancestors["notify"], set(&[]));
+    }
+
+    #[test]
+    fn ancestors_reject_cycles() {
+        let steps = vec![
+            step("a", &[]),
+            step("b", &["a", "d"]),
+            step("c", &["b"]),
+            step("d", &["c"]),
+        ];
+        assert!(WorkflowService::ancestors(&steps).is_err());
+    }
+
+    #[test]
+    fn run_conditions_follow_upstream_statuses() {
+        use StepRunStatus::*;
+        let condition_met = WorkflowService::condition_met;
+        assert!(condition_met(
+            RunCondition::Success,
+            &[Completed, Completed]
+        ));
+        assert!(!condition_met(RunCondition::Success, &[Completed, Skipped]));
+        assert!(condition_met(RunCondition::Failure, &[Completed, Failed]));
+        assert!(!condition_met(RunCondition::Failure, &[Completed, Skipped]));
+        assert!(condition_met(RunCondition::Always, &[Failed, Skipped]));
+    }
+}
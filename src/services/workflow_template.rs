@@ -0,0 +1,304 @@
+// 模板形如 {{ steps.<id>.result.files }}，支持 result[.<key>...]、artifacts.<name>、status、exit_code、execution_id
+// 整个字符串只有一个模板时保留引用值的 JSON 类型，否则替换为文本
+
+use crate::error::{AppError, Result};
+use crate::models::Execution;
+use crate::services::artifact_store::ArtifactStore;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+enum Segment<'a> {
+    Text(&'a str),
+    Template(&'a str),
+}
+
+struct Reference<'a> {
+    step_id: &'a str,
+    field: Field<'a>,
+}
+
+enum Field<'a> {
+    Result(Vec<&'a str>),
+    Artifact(String),
+    Status,
+    ExitCode,
+    ExecutionId,
+}
+
+pub fn referenced_steps(value: &Value) -> Result<Vec<String>> {
+    let mut steps = Vec::new();
+    collect_references(value, &mut steps)?;
+    Ok(steps)
+}
+
+fn collect_references(value: &Value, steps: &mut Vec<String>) -> Result<()> {
+    match value {
+        Value::String(text) => {
+            for segment in segments(text)? {
+                if let Segment::Template(expr) = segment {
+                    steps.push(parse_reference(expr)?.step_id.to_string());
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_references(item, steps)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_references(item, steps)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn render(value: &Value, executions: &HashMap<String, Execution>) -> Result<Value> {
+    match value {
+        Value::String(text) => {
+            let segments = segments(text)?;
+            if let [Segment::Template(expr)] = segments.as_slice() {
+                return resolve(expr, executions);
+            }
+            let mut rendered = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Text(text) => rendered.push_str(text),
+                    Segment::Template(expr) => match resolve(expr, executions)? {
+                        Value::String(text) => rendered.push_str(&text),
+                        other => rendered.push_str(&other.to_string()),
+                    },
+                }
+            }
+            Ok(Value::String(rendered))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| render(item, executions))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        Value::Object(map) => {
+            let mut rendered = serde_json::Map::new();
+            for (key, item) in map {
+                rendered.insert(key.clone(), render(item, executions)?);
+            }
+            Ok(Value::Object(rendered))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+fn segments(text: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let after = &rest[start + OPEN.len()..];
+        let Some(end) = after.find(CLOSE) else {
+            return Err(AppError::Workflow(format!(
+                "Unterminated template in '{}'",
+                text
+            )));
+        };
+        segments.push(Segment::Template(after[..end].trim()));
+        rest = &after[end + CLOSE.len()..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+fn parse_reference(expr: &str) -> Result<Reference<'_>> {
+    let invalid = || AppError::Workflow(format!("Invalid template '{{{{ {} }}}}'", expr));
+    let mut parts = expr.split('.');
+    let (Some("steps"), Some(step_id), Some(field)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    if step_id.is_empty() {
+        return Err(invalid());
+    }
+
+    let rest: Vec<&str> = parts.collect();
+    let field = match (field, rest.as_slice()) {
+        ("result", path) => Field::Result(path.to_vec()),
+        ("artifacts", name) if !name.is_empty() => Field::Artifact(name.join(".")),
+        ("status", []) => Field::Status,
+        ("exit_code", []) => Field::ExitCode,
+        ("execution_id", []) => Field::ExecutionId,
+        _ => return Err(invalid()),
+    };
+    Ok(Reference { step_id, field })
+}
+
+fn resolve(expr: &str, executions: &HashMap<String, Execution>) -> Result<Value> {
+    let reference = parse_reference(expr)?;
+    let step_id = reference.step_id;
+    let execution = executions
+        .get(step_id)
+        .ok_or_else(|| AppError::Workflow(format!("Step '{}' did not run", step_id)))?;
+
+    match reference.field {
+        Field::Result(path) => {
+            let raw = execution.result.as_deref().ok_or_else(|| {
+                AppError::Workflow(format!("Step '{}' reported no result", step_id))
+            })?;
+            let mut value: Value = serde_json::from_str(raw).map_err(|e| {
+                AppError::Workflow(format!("Invalid result of step '{}': {}", step_id, e))
+            })?;
+            for key in &path {
+                let next = match &mut value {
+                    Value::Object(map) => map.remove(*key),
+                    Value::Array(items) => key
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|index| *index < items.len())
+                        .map(|index| items.swap_remove(index)),
+                    _ => None,
+                };
+                value = next.ok_or_else(|| {
+                    AppError::Workflow(format!(
+                        "Result of step '{}' has no '{}'",
+                        step_id,
+                        path.join(".")
+                    ))
+                })?;
+            }
+            Ok(value)
+        }
+        Field::Artifact(name) => {
+            let (_, path) = ArtifactStore::find(&execution.id, &name).map_err(|_| {
+                AppError::Workflow(format!("Step '{}' has no artifact '{}'", step_id, name))
+            })?;
+            Ok(Value::String(path.to_string_lossy().to_string()))
+        }
+        Field::Status => Ok(serde_json::to_value(execution.status).unwrap_or(Value::Null)),
+        Field::ExitCode => Ok(execution.exit_code.map(Value::from).unwrap_or(Value::Null)),
+        Field::ExecutionId => Ok(Value::String(execution.id.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExecutionPhase, ExecutionProgress, ExecutionStatus};
+    use serde_json::json;
+
+    fn finished(id: &str, status: ExecutionStatus, result: Option<Value>) -> Execution {
+        Execution {
+            id: id.to_string(),
+            plugin_id: "plugin".to_string(),
+            phase: ExecutionPhase::Prepare,
+            status,
+            pid: None,
+            exit_code: Some(0),
+            stdout: None,
+            stderr: None,
+            preview_payload: None,
+            result: result.map(|value| value.to_string()),
+            error_message: None,
+            progress: ExecutionProgress::default(),
+            input_request: None,
+            params: None,
+            plugin_version: None,
+            entry_point: None,
+            rerun_of: None,
+            triggered_by: None,
+            confirm_token: None,
+            expires_at: None,
+            started_at: 0,
+            finished_at: Some(0),
+            queue_position: None,
+        }
+    }
+
+    fn scan_step() -> HashMap<String, Execution> {
+        let result = json!({ "files": ["a.txt", "b.txt"], "count": 2 });
+        HashMap::from([(
+            "scan".to_string(),
+            finished("exec-1", ExecutionStatus::Completed, Some(result)),
+        )])
+    }
+
+    #[test]
+    fn collects_referenced_steps_from_nested_values() {
+        let value = json!({
+            "path": "{{ steps.scan.result.files }}",
+            "list": ["x", "{{steps.build.status}} and {{ steps.scan.exit_code }}"],
+            "count": 3
+        });
+        let mut steps = referenced_steps(&value).unwrap();
+        steps.sort();
+        assert_eq!(steps, vec!["build", "scan", "scan"]);
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for text in [
+            "{{ steps.scan.result",
+            "{{ scan.result }}",
+            "{{ steps..status }}",
+            "{{ steps.scan.status.extra }}",
+            "{{ steps.scan.artifacts }}",
+            "{{ steps.scan.unknown }}",
+        ] {
+            assert!(referenced_steps(&json!(text)).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn whole_template_keeps_the_json_type() {
+        let executions = scan_step();
+        let rendered = render(&json!("{{ steps.scan.result.files }}"), &executions).unwrap();
+        assert_eq!(rendered, json!(["a.txt", "b.txt"]));
+        let rendered = render(&json!("{{ steps.scan.result.count }}"), &executions).unwrap();
+        assert_eq!(rendered, json!(2));
+    }
+
+    #[test]
+    fn embedded_templates_render_as_text() {
+        let executions = scan_step();
+        let value = json!({
+            "summary": "{{ steps.scan.result.count }} files, first {{ steps.scan.result.files.0 }}",
+            "state": "{{ steps.scan.status }}/{{ steps.scan.exit_code }}",
+            "id": "{{ steps.scan.execution_id }}",
+            "flag": true
+        });
+        let rendered = render(&value, &executions).unwrap();
+        assert_eq!(
+            rendered,
+            json!({
+                "summary": "2 files, first a.txt",
+                "state": "Completed/0",
+                "id": "exec-1",
+                "flag": true
+            })
+        );
+    }
+
+    #[test]
+    fn missing_values_are_errors() {
+        let executions = scan_step();
+        for text in [
+            "{{ steps.build.status }}",
+            "{{ steps.scan.result.missing }}",
+            "{{ steps.scan.result.files.5 }}",
+        ] {
+            assert!(render(&json!(text), &executions).is_err(), "{}", text);
+        }
+
+        let executions = HashMap::from([(
+            "scan".to_string(),
+            finished("exec-2", ExecutionStatus::Failed, None),
+        )]);
+        assert!(render(&json!("{{ steps.scan.result }}"), &executions).is_err());
+    }
+}